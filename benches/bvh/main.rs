@@ -0,0 +1,206 @@
+//! 在内置场景与 `scenes/` 下的场景文件上比较不同 BVH 构建方式的构建时间、SAH 代价与求交速度，
+//! 其中 `original` 为最初的构建方式（见 `original.rs`），`sah-tree` 为扁平化之前以指针链接、
+//! 递归遍历的 SAH BVH（见 `pointer_tree.rs`），与 `sah` 的差别只在节点布局与遍历方式；
+//! 在大规模网格上比较单线程与多线程构建，并比较动画中逐帧重建与重新拟合顶层 BVH 的耗时
+//!
+//! 运行：`cargo bench --bench bvh`
+
+mod original;
+mod pointer_tree;
+
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use rtweekend::*;
+
+const WIDTH: u32 = 300;
+const SAMPLES: u32 = 4;
+// 大规模网格为 GRID x GRID 的起伏高度场，共 2 * GRID^2 个三角形
+const GRID: usize = 512;
+// 动画场景中的实例数与帧数
+const INSTANCES: usize = 10000;
+const FRAMES: usize = 20;
+
+fn height_field() -> Vec<ObjectType> {
+    let mut positions = Vec::new();
+    for z in 0..=GRID {
+        for x in 0..=GRID {
+            let (u, v) = (x as f64 / GRID as f64, z as f64 / GRID as f64);
+            let y = 0.1 * (u * 40.0).sin() * (v * 30.0).cos();
+            positions.push(Vec3(u * 10.0 - 5.0, y, v * 10.0 - 5.0));
+        }
+    }
+
+    let vertex = |x: usize, z: usize| VertexIndex {
+        position: z * (GRID + 1) + x,
+        ..Default::default()
+    };
+    let mut triangles = Vec::new();
+    for z in 0..GRID {
+        for x in 0..GRID {
+            triangles.push([vertex(x, z), vertex(x + 1, z), vertex(x + 1, z + 1)]);
+            triangles.push([vertex(x, z), vertex(x + 1, z + 1), vertex(x, z + 1)]);
+        }
+    }
+
+    let mesh = Arc::new(TriangleMesh::new(
+        positions,
+        Vec::new(),
+        Vec::new(),
+        triangles,
+    ));
+    mesh.objects(Arc::new(Box::new(Lambertian::default())), &HashMap::new())
+}
+
+// 主光线与其在击中点处的一次漫反射，各构建方式使用同一组光线
+fn sample_rays(config: &Config) -> Vec<Ray> {
+    let height = (WIDTH as f64 / config.camera.aspect_ratio()) as u32;
+    let mut rays = Vec::new();
+    for j in 0..height {
+        for i in 0..WIDTH {
+            for _ in 0..SAMPLES {
+                let u = (i as f64 + random_01()) / (WIDTH - 1) as f64;
+                let v = (j as f64 + random_01()) / (height - 1) as f64;
+                let ray = config
+                    .camera
+                    .get_ray_upper_left(u, v, &mut IndependentSampler::new());
+                if let Some(record) = config.scene.hit(&ray, (1e-8, f64::INFINITY)) {
+                    let dir = random_hemisphere(record.hit_normal);
+                    rays.push(Ray::new(record.hit_point, dir, 1));
+                }
+                rays.push(ray);
+            }
+        }
+    }
+    rays
+}
+
+// 按名字构建加速结构，返回它与 SAH 代价
+fn build(method: &str, objects: Vec<ObjectType>) -> (Box<dyn Hittable>, f64) {
+    match method {
+        "original" => {
+            let (root, cost) = original::build(objects);
+            (Box::new(root), cost)
+        }
+        "sah-tree" => {
+            let (root, cost) = pointer_tree::build(objects);
+            (Box::new(root), cost)
+        }
+        "median" => {
+            let bvh = BVH::build_with(objects, SplitMethod::Median);
+            let cost = bvh.sah_cost();
+            (Box::new(bvh), cost)
+        }
+        _ => {
+            let bvh = BVH::build_with(objects, SplitMethod::Sah);
+            let cost = bvh.sah_cost();
+            (Box::new(bvh), cost)
+        }
+    }
+}
+
+fn main() {
+    let mut scenes = Vec::new();
+    for name in BUILTIN_SCENES {
+        seed_random(42);
+        scenes.push((name.to_string(), Config::builtin(name).unwrap()));
+    }
+    for name in ["three_spheres", "cornell_box", "cornell_smoke", "cloud"] {
+        let path = format!("scenes/{}.toml", name);
+        scenes.push((path.clone(), Config::from_file(&path).unwrap()));
+    }
+
+    println!(
+        "{:<26} {:<8} {:>8} {:>8} {:>12} {:>10} {:>12} {:>10}",
+        "scene", "method", "objects", "rays", "build (ms)", "SAH cost", "trace (ms)", "Mrays/s"
+    );
+    for (name, config) in &scenes {
+        seed_random(7);
+        let rays = sample_rays(config);
+
+        for method in ["original", "median", "sah-tree", "sah"] {
+            // 最初的构建方式随机选择划分轴，固定种子使结果可复现
+            seed_random(3);
+            let start = Instant::now();
+            let (bvh, cost) = build(method, config.scene.objects.clone());
+            let build = start.elapsed();
+
+            seed_random(11);
+            let start = Instant::now();
+            for ray in &rays {
+                bvh.hit(ray, (1e-8, f64::INFINITY));
+            }
+            let trace = start.elapsed();
+
+            println!(
+                "{:<26} {:<8} {:>8} {:>8} {:>12.3} {:>10.2} {:>12.1} {:>10.2}",
+                name,
+                method,
+                config.scene.objects.len(),
+                rays.len(),
+                build.as_secs_f64() * 1e3,
+                cost,
+                trace.as_secs_f64() * 1e3,
+                rays.len() as f64 / trace.as_secs_f64() / 1e6,
+            );
+        }
+    }
+
+    let objects = height_field();
+    let mut thread_counts = vec![1];
+    if num_cpus::get() > 1 {
+        thread_counts.push(num_cpus::get());
+    }
+    println!();
+    for threads in thread_counts {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let bvh = pool.install(|| BVH::build_with(objects.clone(), SplitMethod::Sah));
+        println!("{} thread(s): {}", threads, bvh.stats());
+    }
+
+    let blas: ObjectType = Arc::new(Box::new(BVH::build(height_field())));
+    let matrix = |i: usize, frame: usize| {
+        let phase = i as f64 * 0.37 + frame as f64 * 0.1;
+        Mat4::translate(Vec3(
+            (i % 100) as f64 * 12.0 + phase.sin(),
+            phase.cos(),
+            (i / 100) as f64 * 12.0,
+        ))
+    };
+    let mut scene = Scene::new();
+    for i in 0..INSTANCES {
+        scene.add_instance(blas.clone(), matrix(i, 0)).unwrap();
+    }
+    scene.build_bvh();
+
+    println!();
+    for refit in [false, true] {
+        let (mut elapsed, mut rebuilds) = (0.0, 0);
+        for frame in 1..=FRAMES {
+            for i in 0..INSTANCES {
+                scene.set_instance_matrix(i, matrix(i, frame)).unwrap();
+            }
+            let start = Instant::now();
+            let rebuilt = match refit {
+                true => scene.refit_bvh(),
+                false => {
+                    scene.build_bvh();
+                    true
+                }
+            };
+            elapsed += start.elapsed().as_secs_f64();
+            rebuilds += rebuilt as usize;
+        }
+        println!(
+            "{} instances, {} frames, {}: {:.3} ms per frame ({} rebuilds), SAH cost {:.2}",
+            INSTANCES,
+            FRAMES,
+            if refit { "refit" } else { "rebuild" },
+            elapsed / FRAMES as f64 * 1e3,
+            rebuilds,
+            scene.bvh.sah_cost()
+        );
+    }
+}
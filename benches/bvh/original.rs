@@ -0,0 +1,103 @@
+//! 最初的 BVH 构建方式的原样副本，作为对照：随机选择划分轴，按包围盒下界截断为 i32 后排序，
+//! 再对半划分到一至两个物体，子节点以 `Arc<Box<dyn Bounded>>` 存放并递归遍历
+
+use std::sync::Arc;
+
+use rtweekend::*;
+
+// 与库中相同的内部节点遍历代价，使 SAH 代价与 `BVH::sah_cost` 口径一致
+const TRAVERSAL_COST: f64 = 0.125;
+
+pub struct Node {
+    left: ObjectType,
+    right: ObjectType,
+    aabb: AABB,
+}
+
+/// 按最初的方式构建，返回根节点与按 SAH 估计的期望求交代价
+pub fn build(objects: Vec<ObjectType>) -> (Node, f64) {
+    recursive_build(objects)
+}
+
+fn recursive_build(mut list: Vec<ObjectType>) -> (Node, f64) {
+    // 一至两个物体的节点总是对左右两侧各求交一次
+    let length = list.len();
+    if length == 1 {
+        return (
+            Node {
+                left: list[0].clone(),
+                right: list[0].clone(),
+                aabb: list[0].bounding_box(),
+            },
+            2.0,
+        );
+    } else if length == 2 {
+        return (
+            Node {
+                left: list[0].clone(),
+                right: list[1].clone(),
+                aabb: list[0].bounding_box() + list[1].bounding_box(),
+            },
+            2.0,
+        );
+    }
+
+    let axis = random_int(0, 2);
+
+    list.sort_by_key(|key| key.bounding_box().min().get(axis as usize) as i32);
+
+    let (left, right) = list.split_at_mut(length / 2);
+
+    let (left_node, left_cost) = recursive_build(left.to_vec());
+    let (right_node, right_cost) = recursive_build(right.to_vec());
+    let aabb = left_node.bounding_box() + right_node.bounding_box();
+
+    let area = aabb.surface_area();
+    let cost = match area > 0.0 {
+        true => {
+            TRAVERSAL_COST
+                + (left_node.aabb.surface_area() * left_cost
+                    + right_node.aabb.surface_area() * right_cost)
+                    / area
+        }
+        false => TRAVERSAL_COST + left_cost + right_cost,
+    };
+
+    let result = Node {
+        left: Arc::new(Box::new(left_node)),
+        right: Arc::new(Box::new(right_node)),
+        aabb,
+    };
+
+    (result, cost)
+}
+
+impl Hittable for Node {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let range = self.aabb.hit(ray, t_range)?;
+
+        let mut left_result = self.left.hit(ray, range);
+        let right_result = self.right.hit(
+            ray,
+            match left_result {
+                Some(temp_result) => {
+                    let t = temp_result.t;
+                    left_result = Option::Some(temp_result);
+                    (range.0, t)
+                }
+                None => range,
+            },
+        );
+
+        match right_result {
+            Some(_) => right_result,
+            None => left_result,
+        }
+    }
+}
+
+impl Bounded for Node {
+    fn bounding_box(&self) -> AABB {
+        self.aabb
+    }
+}
@@ -0,0 +1,211 @@
+//! 扁平化之前的 SAH BVH 副本，作为对照：划分方式与 `SplitMethod::Sah` 相同，
+//! 但节点以 `Box` 链接成树并递归遍历，每个节点对光线方向重新求倒数，也不按远近顺序访问子节点
+
+use rtweekend::*;
+
+// 与库中相同的常量
+const SAH_BINS: usize = 12;
+const TRAVERSAL_COST: f64 = 0.125;
+
+pub enum Node {
+    Leaf {
+        objects: Vec<ObjectType>,
+        aabb: AABB,
+    },
+    Interior {
+        left: Box<Node>,
+        right: Box<Node>,
+        aabb: AABB,
+    },
+}
+
+/// 按 SAH 构建指针树，返回根节点与其 SAH 代价
+pub fn build(objects: Vec<ObjectType>) -> (Node, f64) {
+    let mut primitives: Vec<Primitive> = objects.into_iter().map(Primitive::new).collect();
+    let root = build_sah(&mut primitives);
+    let cost = root.cost();
+    (root, cost)
+}
+
+struct Primitive {
+    object: ObjectType,
+    aabb: AABB,
+    centroid: Point3,
+}
+
+impl Primitive {
+    fn new(object: ObjectType) -> Primitive {
+        let aabb = object.bounding_box();
+        Primitive {
+            object,
+            aabb,
+            centroid: aabb.centroid(),
+        }
+    }
+}
+
+fn bounds_of(primitives: &[Primitive]) -> AABB {
+    primitives[1..]
+        .iter()
+        .fold(primitives[0].aabb, |result, primitive| {
+            result + primitive.aabb
+        })
+}
+
+fn leaf(primitives: &[Primitive], aabb: AABB) -> Node {
+    Node::Leaf {
+        objects: primitives
+            .iter()
+            .map(|primitive| primitive.object.clone())
+            .collect(),
+        aabb,
+    }
+}
+
+fn interior(primitives: &mut [Primitive], mid: usize, aabb: AABB) -> Node {
+    let (left, right) = primitives.split_at_mut(mid);
+    Node::Interior {
+        left: Box::new(build_sah(left)),
+        right: Box::new(build_sah(right)),
+        aabb,
+    }
+}
+
+fn bin_index(value: f64, low: f64, high: f64) -> usize {
+    (((value - low) / (high - low) * SAH_BINS as f64) as usize).min(SAH_BINS - 1)
+}
+
+fn build_sah(primitives: &mut [Primitive]) -> Node {
+    let aabb = bounds_of(primitives);
+    let length = primitives.len();
+    if length == 1 {
+        return leaf(primitives, aabb);
+    }
+
+    let first = primitives[0].centroid;
+    let centroid_bounds = primitives[1..]
+        .iter()
+        .fold(AABB::new(first, first), |result, primitive| {
+            result + AABB::new(primitive.centroid, primitive.centroid)
+        });
+    let parent_area = aabb.surface_area();
+
+    // (代价, 轴, 左侧包含的最后一个桶)
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        let (low, high) = (
+            centroid_bounds.min().get(axis),
+            centroid_bounds.max().get(axis),
+        );
+        if high <= low {
+            continue;
+        }
+
+        let mut bins: [(usize, Option<AABB>); SAH_BINS] = [(0, None); SAH_BINS];
+        for primitive in primitives.iter() {
+            let bin = &mut bins[bin_index(primitive.centroid.get(axis), low, high)];
+            bin.0 += 1;
+            bin.1 = Some(bin.1.map_or(primitive.aabb, |aabb| aabb + primitive.aabb));
+        }
+
+        // 从右向左累计每个划分右侧的图元数与表面积
+        let mut right_costs = [0.0; SAH_BINS];
+        let (mut count, mut bounds) = (0, None::<AABB>);
+        for split in (1..SAH_BINS).rev() {
+            count += bins[split].0;
+            bounds = union(bounds, bins[split].1);
+            right_costs[split - 1] = count as f64 * bounds.map_or(0.0, |b| b.surface_area());
+        }
+
+        let (mut count, mut bounds) = (0, None::<AABB>);
+        for split in 0..SAH_BINS - 1 {
+            count += bins[split].0;
+            bounds = union(bounds, bins[split].1);
+            let left_cost = count as f64 * bounds.map_or(0.0, |b| b.surface_area());
+            let cost = TRAVERSAL_COST + (left_cost + right_costs[split]) / parent_area;
+            if count > 0 && count < length && best.is_none_or(|(best, _, _)| cost < best) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+
+    match best {
+        Some((cost, axis, split)) if length > MAX_LEAF_SIZE || cost < length as f64 => {
+            let (low, high) = (
+                centroid_bounds.min().get(axis),
+                centroid_bounds.max().get(axis),
+            );
+            let mut mid = 0;
+            for i in 0..length {
+                if bin_index(primitives[i].centroid.get(axis), low, high) <= split {
+                    primitives.swap(i, mid);
+                    mid += 1;
+                }
+            }
+            interior(primitives, mid, aabb)
+        }
+        Some(_) => leaf(primitives, aabb),
+        // 所有中心重合，无法按位置区分，只能按数量对半划分
+        None if length > MAX_LEAF_SIZE => interior(primitives, length / 2, aabb),
+        None => leaf(primitives, aabb),
+    }
+}
+
+fn union(lhs: Option<AABB>, rhs: Option<AABB>) -> Option<AABB> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+        (lhs, None) => lhs,
+        (None, rhs) => rhs,
+    }
+}
+
+impl Node {
+    fn cost(&self) -> f64 {
+        match self {
+            Node::Leaf { objects, .. } => objects.len() as f64,
+            Node::Interior { left, right, aabb } => {
+                let area = aabb.surface_area();
+                if area <= 0.0 {
+                    return TRAVERSAL_COST + left.cost() + right.cost();
+                }
+                TRAVERSAL_COST
+                    + (left.bounding_box().surface_area() * left.cost()
+                        + right.bounding_box().surface_area() * right.cost())
+                        / area
+            }
+        }
+    }
+}
+
+impl Hittable for Node {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        self.bounding_box().hit(ray, t_range)?;
+
+        match self {
+            Node::Leaf { objects, .. } => {
+                let mut result = Option::None;
+                let mut closest_so_far = t_range.1;
+                for object in objects {
+                    if let Some(record) = object.hit(ray, (t_range.0, closest_so_far)) {
+                        closest_so_far = record.t;
+                        result = Option::Some(record);
+                    }
+                }
+                result
+            }
+            Node::Interior { left, right, .. } => {
+                let left_result = left.hit(ray, t_range);
+                let closest_so_far = left_result.as_ref().map_or(t_range.1, |record| record.t);
+                right.hit(ray, (t_range.0, closest_so_far)).or(left_result)
+            }
+        }
+    }
+}
+
+impl Bounded for Node {
+    fn bounding_box(&self) -> AABB {
+        match self {
+            Node::Leaf { aabb, .. } | Node::Interior { aabb, .. } => *aabb,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{f64::consts::PI, path::Path};
+
+use crate::*;
+
+use image::{ImageError, Rgb32FImage};
+
+/// 光线未击中任何物体时返回的环境辐亮度
+pub trait Background {
+    fn radiance(&self, dir: Vec3) -> Color;
+}
+
+pub struct SolidBackground {
+    color: Color,
+}
+
+impl SolidBackground {
+    pub fn new(color: Color) -> SolidBackground {
+        SolidBackground { color }
+    }
+}
+
+impl Background for SolidBackground {
+    fn radiance(&self, _dir: Vec3) -> Color {
+        self.color
+    }
+}
+
+/// 沿 y 方向从 `bottom` 渐变到 `top` 的天空
+pub struct GradientBackground {
+    bottom: Color,
+    top: Color,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: Color, top: Color) -> GradientBackground {
+        GradientBackground { bottom, top }
+    }
+}
+
+impl Default for GradientBackground {
+    fn default() -> Self {
+        GradientBackground::new(
+            Color::new_color(1.0, 1.0, 1.0),
+            Color::new_color(0.5, 0.7, 1.0),
+        )
+    }
+}
+
+impl Background for GradientBackground {
+    fn radiance(&self, dir: Vec3) -> Color {
+        let t = 0.5 * (dir.unit_vector().y() + 1.0);
+        self.bottom * (1.0 - t) + self.top * t
+    }
+}
+
+/// 等距柱状投影（equirectangular）的 HDR 环境贴图，`rotation` 为绕 y 轴旋转的角度
+pub struct EnvironmentMap {
+    image: Rgb32FImage,
+    rotation: f64,
+    intensity: f64,
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Rgb32FImage, rotation: f64, intensity: f64) -> EnvironmentMap {
+        EnvironmentMap {
+            image,
+            rotation,
+            intensity,
+        }
+    }
+
+    /// 读取 Radiance `.hdr`、OpenEXR 或其他 `image` 支持的格式
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        rotation: f64,
+        intensity: f64,
+    ) -> Result<EnvironmentMap, ImageError> {
+        Ok(EnvironmentMap::new(
+            open_linear_image(path)?,
+            rotation,
+            intensity,
+        ))
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let (width, height) = (self.image.width() as i64, self.image.height() as i64);
+        let x = x.rem_euclid(width) as u32;
+        let y = y.clamp(0, height - 1) as u32;
+        let pixel = self.image.get_pixel(x, y);
+        Color::new_color(pixel[0] as f64, pixel[1] as f64, pixel[2] as f64)
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn radiance(&self, dir: Vec3) -> Color {
+        let dir = dir.unit_vector();
+        let phi = f64::atan2(dir.x(), -dir.z()) + self.rotation.to_radians();
+        let theta = dir.y().clamp(-1.0, 1.0).acos();
+
+        // 双线性插值，水平方向环绕
+        let x = (phi / (2.0 * PI) + 0.5).rem_euclid(1.0) * self.image.width() as f64 - 0.5;
+        let y = theta / PI * self.image.height() as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let color = self.texel(x0, y0) * ((1.0 - fx) * (1.0 - fy))
+            + self.texel(x0 + 1, y0) * (fx * (1.0 - fy))
+            + self.texel(x0, y0 + 1) * ((1.0 - fx) * fy)
+            + self.texel(x0 + 1, y0 + 1) * (fx * fy);
+
+        color * self.intensity
+    }
+}
@@ -9,9 +9,11 @@ pub struct Camera {
     lower_left_corner: Point3,
     upper_left_corner: Point3,
     ray_depth: u32,
+    shutter: (f64, f64),
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         look_from: Point3,
         look_at: Point3,
@@ -46,11 +48,45 @@ impl Camera {
             lower_left_corner,
             upper_left_corner,
             ray_depth,
+            shutter: (0.0, 0.0),
         }
     }
 
-    pub fn get_ray_lower_left(&self, u: f64, v: f64) -> Ray {
-        let rd = random_unit_disk() * self.aperture / 2.0;
+    /// 设置快门开启与关闭的时刻，光线的时间在此区间内均匀采样
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Camera {
+        self.shutter = (open, close);
+        self
+    }
+
+    pub fn shutter(&self) -> (f64, f64) {
+        self.shutter
+    }
+
+    fn sample_time(&self, u: f64) -> f64 {
+        self.shutter.0 + (self.shutter.1 - self.shutter.0) * u
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.horizontal.length() / self.vertical.length()
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f64) {
+        let center = self.lower_left_corner + self.horizontal / 2.0 + self.vertical / 2.0;
+        self.horizontal = self.horizontal.unit_vector() * self.vertical.length() * aspect_ratio;
+        self.lower_left_corner = center - self.horizontal / 2.0 - self.vertical / 2.0;
+        self.upper_left_corner = center - self.horizontal / 2.0 + self.vertical / 2.0;
+    }
+
+    pub fn ray_depth(&self) -> u32 {
+        self.ray_depth
+    }
+
+    pub fn set_ray_depth(&mut self, ray_depth: u32) {
+        self.ray_depth = ray_depth;
+    }
+
+    pub fn get_ray_lower_left(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Ray {
+        let rd = sample_unit_disk(sampler.get_2d()) * self.aperture / 2.0;
         let offset = self.uvw.0 * rd.x() + self.uvw.1 * rd.y();
 
         Ray {
@@ -59,11 +95,12 @@ impl Camera {
                 - self.origin
                 - offset,
             depth: self.ray_depth,
+            time: self.sample_time(sampler.get_1d()),
         }
     }
 
-    pub fn get_ray_upper_left(&self, u: f64, v: f64) -> Ray {
-        let rd = random_unit_disk() * self.aperture / 2.0;
+    pub fn get_ray_upper_left(&self, u: f64, v: f64, sampler: &mut dyn Sampler) -> Ray {
+        let rd = sample_unit_disk(sampler.get_2d()) * self.aperture / 2.0;
         let offset = self.uvw.0 * rd.x() + self.uvw.1 * rd.y();
 
         Ray {
@@ -73,6 +110,7 @@ impl Camera {
                 - self.origin
                 - offset,
             depth: self.ray_depth,
+            time: self.sample_time(sampler.get_1d()),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::*;
 
 pub struct Config {
@@ -9,19 +11,70 @@ pub struct Config {
     pub image_height: u32,
     pub samples_per_pixel: u32,
 
+    pub threads: usize,
+    /// 随机数种子，渲染结果只取决于它，与线程数和区块的完成顺序无关
+    pub seed: u64,
+    pub tile_size: u32,
+    pub tile_order: TileOrder,
+    /// 按 1、2、4……spp 分遍渲染，并定期把中间结果写到 `file_path`
+    pub progressive: bool,
+    pub snapshot_interval: Duration,
+    pub sampler: SamplerKind,
+    /// 像素重建滤波器
+    pub filter: Filter,
+    /// 输出 8 位图像时的曝光与色调映射
+    pub tone_mapping: ToneMapping,
+
     pub scene: Scene,
 }
 
+pub const BUILTIN_SCENES: [&str; 2] = ["cover", "three_spheres"];
+
+pub const DEFAULT_TILE_SIZE: u32 = 32;
+
+pub const DEFAULT_SEED: u64 = 0;
+
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
 impl Default for Config {
     fn default() -> Self {
+        Self::preset(
+            Point3::new_point3(13.0, 2.0, 3.0),
+            Point3::new_point3(0.0, 0.0, 0.0),
+            10.0,
+            test_scene(),
+        )
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "cover" => Some(Self::default()),
+            "three_spheres" => {
+                let look_from = Point3::new_point3(3.0, 3.0, 2.0);
+                let look_at = Point3::new_point3(0.0, 0.0, -1.0);
+                Some(Self::preset(
+                    look_from,
+                    look_at,
+                    (look_from - look_at).length(),
+                    initial_scene(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn preset(look_from: Point3, look_at: Point3, focus_distance: f64, scene: Scene) -> Self {
         //Camera Settings
-        let look_from = Point3::new_point3(13.0, 2.0, 3.0);
-        let look_at = Point3::new_point3(0.0, 0.0, 0.0);
         let view_up = Vec3(0.0, 1.0, 0.0);
         let field_of_view = 20.0;
         let aspect_ratio = 3.0 / 2.0;
         let aperture = 0.1;
-        let focus_distance = 10.0;
         let ray_depth: u32 = 50;
 
         //Film Settings
@@ -44,18 +97,30 @@ impl Default for Config {
             image_width,
             image_height,
             samples_per_pixel,
-            scene: test_scene(),
+            threads: num_cpus::get(),
+            seed: DEFAULT_SEED,
+            tile_size: DEFAULT_TILE_SIZE,
+            tile_order: TileOrder::default(),
+            progressive: false,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            sampler: SamplerKind::default(),
+            filter: Filter::default(),
+            tone_mapping: ToneMapping::default(),
+            scene,
         }
     }
-}
 
-impl Config {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SceneError> {
+        load_config(path)
+    }
+
+    /// 按 `sampler` 创建采样器，扰乱方式由 `seed` 决定
+    pub fn create_sampler(&self) -> Box<dyn Sampler> {
+        self.sampler.create(self.samples_per_pixel, self.seed)
     }
 }
 
-fn initial_scene() -> Scene {
+pub fn initial_scene() -> Scene {
     let mut scene = Scene::new();
 
     let material_ground: MaterialType =
@@ -105,7 +170,7 @@ fn initial_scene() -> Scene {
     scene
 }
 
-fn test_scene() -> Scene {
+pub fn test_scene() -> Scene {
     let mut scene = Scene::new();
 
     let material_ground: MaterialType =
@@ -0,0 +1,133 @@
+use crate::*;
+
+use image::Rgb32FImage;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct FilmPixel {
+    // 线性颜色的加权和
+    color: [f32; 3],
+    weight: f32,
+}
+
+/// 胶片：采样经重建滤波器溅射（splat）到半径内的各个像素，保存加权和与权重和
+///
+/// 可以只覆盖整幅图像中的一个矩形区域，渲染线程各自写入区块对应的胶片再合并
+#[derive(Debug, Clone)]
+pub struct Film {
+    filter: Filter,
+    // 覆盖区域在整幅图像中的位置
+    origin: (u32, u32),
+    width: u32,
+    height: u32,
+    pixels: Vec<FilmPixel>,
+    // 溅射时各列的权重，长度按滤波器半径确定，避免每个采样重新分配
+    column_weights: Vec<f64>,
+}
+
+impl Film {
+    pub fn new(width: u32, height: u32, filter: Filter) -> Film {
+        Film::with_region(filter, (0, 0), width, height)
+    }
+
+    /// 区块在 `width` x `height` 的图像中可能溅射到的区域，即向四周扩展滤波器半径后与图像的交集
+    pub fn for_tile(tile: Tile, width: u32, height: u32, filter: Filter) -> Film {
+        let margin = filter.radius.ceil() as u32;
+        let (x0, y0) = (tile.x.saturating_sub(margin), tile.y.saturating_sub(margin));
+        let x1 = (tile.x + tile.width + margin).min(width);
+        let y1 = (tile.y + tile.height + margin).min(height);
+        Film::with_region(filter, (x0, y0), x1 - x0, y1 - y0)
+    }
+
+    fn with_region(filter: Filter, origin: (u32, u32), width: u32, height: u32) -> Film {
+        Film {
+            filter,
+            origin,
+            width,
+            height,
+            pixels: vec![FilmPixel::default(); width as usize * height as usize],
+            column_weights: vec![0.0; 2 * filter.radius.ceil() as usize + 1],
+        }
+    }
+
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// 添加一个位于图像坐标 `position` 的采样，像素 (x, y) 的中心在 (x + 0.5, y + 0.5)
+    pub fn add_sample(&mut self, position: (f64, f64), color: Color) {
+        // 采样相对覆盖区域中像素 (0, 0) 中心的位置
+        let px = position.0 - self.origin.0 as f64 - 0.5;
+        let py = position.1 - self.origin.1 as f64 - 0.5;
+        let radius = self.filter.radius;
+        let range = |center: f64, size: u32| {
+            let first = (center - radius).ceil().max(0.0) as i64;
+            let last = ((center + radius).floor() as i64).min(size as i64 - 1);
+            first..=last
+        };
+
+        // 滤波器可分离，两个方向的权重各算一次
+        let (columns, rows) = (range(px, self.width), range(py, self.height));
+        for (weight, x) in self.column_weights.iter_mut().zip(columns.clone()) {
+            *weight = self.filter.evaluate_1d(x as f64 - px);
+        }
+
+        for y in rows {
+            let row_weight = self.filter.evaluate_1d(y as f64 - py);
+            for (x, &column_weight) in columns.clone().zip(&self.column_weights) {
+                let weight = row_weight * column_weight;
+                if weight == 0.0 {
+                    continue;
+                }
+                let pixel = &mut self.pixels[(y * self.width as i64 + x) as usize];
+                pixel.color[0] += (color.0 * weight) as f32;
+                pixel.color[1] += (color.1 * weight) as f32;
+                pixel.color[2] += (color.2 * weight) as f32;
+                pixel.weight += weight as f32;
+            }
+        }
+    }
+
+    /// 把覆盖部分区域的胶片累加进来
+    pub fn merge(&mut self, other: &Film) {
+        for y in 0..other.height {
+            for x in 0..other.width {
+                let (image_x, image_y) = (other.origin.0 + x, other.origin.1 + y);
+                let (Some(local_x), Some(local_y)) = (
+                    image_x.checked_sub(self.origin.0),
+                    image_y.checked_sub(self.origin.1),
+                ) else {
+                    continue;
+                };
+                if local_x >= self.width || local_y >= self.height {
+                    continue;
+                }
+                let source = other.pixels[(y * other.width + x) as usize];
+                let target = &mut self.pixels[(local_y * self.width + local_x) as usize];
+                for (target, source) in target.color.iter_mut().zip(source.color) {
+                    *target += source;
+                }
+                target.weight += source.weight;
+            }
+        }
+    }
+
+    /// 像素 (x, y) 的重建结果，坐标相对于覆盖区域；没有采样或权重和非正时为黑色
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let pixel = self.pixels[(y * self.width + x) as usize];
+        match pixel.weight > 0.0 {
+            true => {
+                let [r, g, b] = pixel.color.map(|c| c as f64 / pixel.weight as f64);
+                Color::new_color(r, g, b)
+            }
+            false => Color::default(),
+        }
+    }
+
+    /// 重建后的线性图像
+    pub fn to_image(&self) -> Rgb32FImage {
+        Rgb32FImage::from_fn(self.width, self.height, |x, y| {
+            let color = self.pixel(x, y);
+            image::Rgb([color.0 as f32, color.1 as f32, color.2 as f32])
+        })
+    }
+}
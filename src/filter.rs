@@ -0,0 +1,140 @@
+use std::{f64::consts::PI, fmt::Display, str::FromStr};
+
+/// 重建滤波器的种类
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    /// 半径内等权，半径 0.5 时即每个像素内采样的平均
+    #[default]
+    Box,
+    /// 线性衰减的三角形
+    Tent,
+    /// 标准差为半径的 1/3，减去半径处的取值使其在边界衰减到零
+    Gaussian,
+    /// Mitchell–Netravali（B = C = 1/3），有少量负瓣
+    Mitchell,
+    /// 以半径为窗口的 Lanczos 窗口 sinc，锐利但有振铃
+    Lanczos,
+}
+
+impl FilterKind {
+    pub fn default_radius(&self) -> f64 {
+        match self {
+            FilterKind::Box => 0.5,
+            FilterKind::Tent => 1.0,
+            FilterKind::Gaussian => 1.5,
+            FilterKind::Mitchell => 2.0,
+            FilterKind::Lanczos => 3.0,
+        }
+    }
+}
+
+impl FromStr for FilterKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "box" => Ok(FilterKind::Box),
+            "tent" => Ok(FilterKind::Tent),
+            "gaussian" => Ok(FilterKind::Gaussian),
+            "mitchell" => Ok(FilterKind::Mitchell),
+            "lanczos" => Ok(FilterKind::Lanczos),
+            _ => Err(format!(
+                "unknown filter `{}` (expected box, tent, gaussian, mitchell or lanczos)",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for FilterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FilterKind::Box => "box",
+            FilterKind::Tent => "tent",
+            FilterKind::Gaussian => "gaussian",
+            FilterKind::Mitchell => "mitchell",
+            FilterKind::Lanczos => "lanczos",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 滤波器半径的上限，过大的半径会让每个采样溅射到过多像素
+pub const MAX_FILTER_RADIUS: f64 = 8.0;
+
+/// 可分离的像素重建滤波器，`radius` 以像素为单位
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub radius: f64,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new(FilterKind::default())
+    }
+}
+
+impl Filter {
+    /// 使用该种类默认半径的滤波器
+    pub fn new(kind: FilterKind) -> Filter {
+        Filter {
+            kind,
+            radius: kind.default_radius(),
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f64) -> Filter {
+        self.radius = radius;
+        self
+    }
+
+    /// 采样相对像素中心偏移 (dx, dy) 时的权重，半径外为零
+    pub fn evaluate(&self, dx: f64, dy: f64) -> f64 {
+        self.evaluate_1d(dx) * self.evaluate_1d(dy)
+    }
+
+    /// 一维的权重，`evaluate` 为两个方向之积
+    pub fn evaluate_1d(&self, x: f64) -> f64 {
+        let (x, r) = (x.abs(), self.radius);
+        if x >= r {
+            return 0.0;
+        }
+        match self.kind {
+            FilterKind::Box => 1.0,
+            FilterKind::Tent => r - x,
+            FilterKind::Gaussian => {
+                let gaussian = |x: f64| (-4.5 * x * x / (r * r)).exp();
+                gaussian(x) - gaussian(r)
+            }
+            FilterKind::Mitchell => mitchell(2.0 * x / r),
+            FilterKind::Lanczos => sinc(x) * sinc(x / r),
+        }
+    }
+}
+
+// B = C = 1/3 的 Mitchell–Netravali 三次样条，x 属于 [0, 2)
+fn mitchell(x: f64) -> f64 {
+    let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+    let value = match x < 1.0 {
+        true => {
+            (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                + (6.0 - 2.0 * b)
+        }
+        false => {
+            (-b - 6.0 * c) * x * x * x
+                + (6.0 * b + 30.0 * c) * x * x
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        }
+    };
+    value / 6.0
+}
+
+fn sinc(x: f64) -> f64 {
+    match x.abs() < 1e-5 {
+        true => 1.0,
+        false => (PI * x).sin() / (PI * x),
+    }
+}
@@ -6,6 +6,8 @@ pub trait Bounded: Hittable {
     fn bounding_box(&self) -> AABB;
 }
 
+pub const AABB_PADDING: f64 = 1e-4;
+
 #[derive(Default, Clone, Copy)]
 pub struct AABB {
     min: Point3,
@@ -25,21 +27,56 @@ impl AABB {
         self.max
     }
 
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let extent = self.max - self.min;
+        2.0 * (extent.x() * extent.y() + extent.y() * extent.z() + extent.z() * extent.x())
+    }
+
+    /// 将厚度过小的维度加厚到 `AABB_PADDING`，避免平面图元的包围盒退化为零体积
+    pub fn pad(&self) -> AABB {
+        let (mut min, mut max) = (self.min, self.max);
+        for axis in 0..3 {
+            if max.get(axis) - min.get(axis) < AABB_PADDING {
+                let mut delta = [0.0; 3];
+                delta[axis] = AABB_PADDING / 2.0;
+                min -= Vec3::from_array(delta);
+                max += Vec3::from_array(delta);
+            }
+        }
+        AABB { min, max }
+    }
+
     pub fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<(f64, f64)> {
+        let inv_dir = Vec3(1.0 / ray.dir.x(), 1.0 / ray.dir.y(), 1.0 / ray.dir.z());
+        self.hit_inverse(ray.orig, inv_dir, t_range)
+    }
+
+    /// 以预先求好的方向倒数做 slab 求交，BVH 遍历时同一条光线只需求一次倒数
+    pub fn hit_inverse(
+        &self,
+        orig: Point3,
+        inv_dir: Vec3,
+        t_range: (f64, f64),
+    ) -> Option<(f64, f64)> {
         let (mut t0, mut t1) = t_range;
         for i in 0..3 {
-            let inv_dir = 1.0 / ray.dir.get(i);
-            let mut _t0 = (self.min.get(i) - ray.orig.get(i)) * inv_dir;
-            let mut _t1 = (self.max.get(i) - ray.orig.get(i)) * inv_dir;
+            let inv_dir = inv_dir.get(i);
+            let mut _t0 = (self.min.get(i) - orig.get(i)) * inv_dir;
+            let mut _t1 = (self.max.get(i) - orig.get(i)) * inv_dir;
             if inv_dir < 0.0 {
                 (_t0, _t1) = (_t1, _t0);
             }
-            if t_range.1.min(_t1) <= t_range.0.max(_t0) {
+            // 与此前各轴的区间求交，而不只是与 `t_range` 比较
+            (t0, t1) = (t0.max(_t0), t1.min(_t1));
+            if t1 <= t0 {
                 return Option::None;
             }
-            (t0, t1) = (t0.max(_t0), t1.min(_t1));
         }
-        return Option::Some((t0, t1));
+        Option::Some((t0, t1))
     }
 }
 
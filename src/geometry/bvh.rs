@@ -1,111 +1,508 @@
+use std::{fmt::Display, time::Duration, time::Instant};
+
 use crate::*;
 
+use rayon::prelude::*;
+
 type BoundedObject = Arc<Box<dyn Bounded + Send + Sync>>;
 type BoundedList = Vec<BoundedObject>;
 
+/// 叶节点最多容纳的图元数，超过时总是继续划分
+pub const MAX_LEAF_SIZE: usize = 4;
+
+/// 重新拟合后 SAH 代价超过构建时的此倍数即完全重建
+pub const REBUILD_THRESHOLD: f64 = 1.5;
+
+// SAH 在每个轴上划分的桶数
+const SAH_BINS: usize = 12;
+// 遍历一个内部节点相对于一次图元求交的代价
+const TRAVERSAL_COST: f64 = 0.125;
+
+/// 构建 BVH 时选择划分位置的策略
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMethod {
+    /// 分桶的表面积启发式：在三个轴上估计每个划分的期望代价，取最小者
+    #[default]
+    Sah,
+    /// 沿包围盒最长的轴按包围盒下界排序后对半划分，至多两个图元时成为叶节点；不估计代价，作为简单的对照
+    Median,
+}
+
+// 遍历栈的容量，构建时以此限制树的深度
+const MAX_DEPTH: usize = 64;
+// 图元数不少于此值的子树才作为并行任务构建，过小的任务调度开销得不偿失
+const PARALLEL_THRESHOLD: usize = 4096;
+// 根节点的父节点下标
+const NO_PARENT: usize = usize::MAX;
+
+/// 扁平化的 BVH：节点按深度优先顺序存放在连续数组中，叶节点引用 `objects` 中连续的一段
+#[derive(Default)]
 pub struct BVH {
-    root: Node,
+    nodes: Vec<LinearNode>,
+    objects: BoundedList,
+    stats: BvhStats,
+    // 以下用于重新拟合：各节点的父节点、构建时第 i 个物体在 `objects` 中的位置、
+    // `objects` 中各物体所在的叶节点，以及包含被替换物体的叶节点
+    parents: Vec<usize>,
+    slots: Vec<usize>,
+    leaf_of: Vec<usize>,
+    dirty: Vec<usize>,
+    method: SplitMethod,
+    build_cost: f64,
 }
 
-impl BVH {
-    pub fn default() -> BVH {
-        let material_default: MaterialType = Arc::new(Box::new(Lambertian::new(Color::new_color(
-            0.999, 0.0, 0.999,
-        ))));
-        let sphere_default: BoundedObject = Arc::new(Box::new(Sphere::new(
-            Vec3(0.0, 0.0, 0.0),
-            1.0,
-            material_default,
-        )));
-        BVH {
-            root: Node {
-                left: sphere_default.clone(),
-                right: sphere_default.clone(),
-                aabb: sphere_default.bounding_box(),
-            },
+/// BVH 的构建耗时与节点统计
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BvhStats {
+    pub primitives: usize,
+    pub nodes: usize,
+    pub leaves: usize,
+    pub max_depth: usize,
+    pub max_leaf_size: usize,
+    pub build_time: Duration,
+}
+
+impl BvhStats {
+    pub fn average_leaf_size(&self) -> f64 {
+        match self.leaves {
+            0 => 0.0,
+            leaves => self.primitives as f64 / leaves as f64,
         }
     }
+}
+
+impl Display for BvhStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BVH: {} primitives, {} nodes ({} leaves, {:.1} primitives per leaf, at most {}), depth {}, built in {:.3}s",
+            self.primitives,
+            self.nodes,
+            self.leaves,
+            self.average_leaf_size(),
+            self.max_leaf_size,
+            self.max_depth,
+            self.build_time.as_secs_f64()
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LinearNode {
+    aabb: AABB,
+    // 叶节点为首个图元在 `objects` 中的下标，内部节点为第二个子节点的下标（第一个子节点紧随其后）
+    offset: usize,
+    // 叶节点的图元数，内部节点为 0
+    count: usize,
+    // 内部节点的划分轴
+    axis: usize,
+}
 
+impl BVH {
     pub fn build(objects: BoundedList) -> BVH {
-        BVH {
-            root: recursive_build(objects),
+        BVH::build_with(objects, SplitMethod::default())
+    }
+
+    /// 在当前的 rayon 线程池（默认为全局池）中构建：图元足够多时左右子树作为并行任务，
+    /// 由空闲线程窃取执行；可在 `rayon::ThreadPool::install` 中调用以限制线程数
+    pub fn build_with(objects: BoundedList, method: SplitMethod) -> BVH {
+        if objects.is_empty() {
+            return BVH::default();
+        }
+
+        let start = Instant::now();
+        let mut primitives = collect_primitives(objects);
+        let root = build_node(&mut primitives, 0, 0, method);
+
+        // 构建过程已将图元就地重排，叶节点覆盖的图元恰为连续的一段
+        let mut slots = vec![0; primitives.len()];
+        for (position, primitive) in primitives.iter().enumerate() {
+            slots[primitive.index] = position;
+        }
+        let mut bvh = BVH {
+            leaf_of: vec![0; primitives.len()],
+            objects: primitives
+                .into_iter()
+                .map(|primitive| primitive.object)
+                .collect(),
+            slots,
+            method,
+            ..Default::default()
+        };
+        bvh.flatten(root, 0, NO_PARENT);
+        bvh.build_cost = bvh.sah_cost();
+        bvh.stats.primitives = bvh.objects.len();
+        bvh.stats.nodes = bvh.nodes.len();
+        bvh.stats.build_time = start.elapsed();
+        bvh
+    }
+
+    /// 替换构建时传入的第 `index` 个物体，树的结构不变；随后调用 `refit` 更新包围盒
+    pub fn replace(&mut self, index: usize, object: BoundedObject) {
+        let position = self.slots[index];
+        self.objects[position] = object;
+        self.dirty.push(self.leaf_of[position]);
+    }
+
+    /// 自底向上更新被替换物体所在叶节点及其祖先的包围盒。
+    /// 物体移动过多使 SAH 代价超过构建时的 `REBUILD_THRESHOLD` 倍时改为完全重建，并返回 `true`
+    pub fn refit(&mut self) -> bool {
+        if self.dirty.is_empty() {
+            return false;
+        }
+
+        for leaf in std::mem::take(&mut self.dirty) {
+            let node = self.nodes[leaf];
+            let objects = &self.objects[node.offset..node.offset + node.count];
+            self.nodes[leaf].aabb = objects[1..]
+                .iter()
+                .fold(objects[0].bounding_box(), |result, object| {
+                    result + object.bounding_box()
+                });
+
+            let mut current = leaf;
+            while self.parents[current] != NO_PARENT {
+                let parent = self.parents[current];
+                let aabb = self.nodes[parent + 1].aabb + self.nodes[self.nodes[parent].offset].aabb;
+                // 包围盒未变时祖先也不会变
+                let old = self.nodes[parent].aabb;
+                if aabb.min() == old.min() && aabb.max() == old.max() {
+                    break;
+                }
+                self.nodes[parent].aabb = aabb;
+                current = parent;
+            }
+        }
+
+        if self.sah_cost() > self.build_cost * REBUILD_THRESHOLD {
+            let objects = self
+                .slots
+                .iter()
+                .map(|position| self.objects[*position].clone())
+                .collect();
+            *self = BVH::build_with(objects, self.method);
+            return true;
+        }
+        false
+    }
+
+    pub fn stats(&self) -> &BvhStats {
+        &self.stats
+    }
+
+    /// 按 SAH 估计的一条光线的期望求交代价，以一次图元求交为单位，用于比较不同构建方式的质量
+    pub fn sah_cost(&self) -> f64 {
+        match self.nodes.is_empty() {
+            true => 0.0,
+            false => self.cost(0),
+        }
+    }
+
+    fn flatten(&mut self, node: Node, depth: usize, parent: usize) -> usize {
+        let index = self.nodes.len();
+        self.parents.push(parent);
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        match node {
+            Node::Leaf { start, count, aabb } => {
+                self.stats.leaves += 1;
+                self.stats.max_leaf_size = self.stats.max_leaf_size.max(count);
+                self.leaf_of[start..start + count].fill(index);
+                self.nodes.push(LinearNode {
+                    aabb,
+                    offset: start,
+                    count,
+                    axis: 0,
+                })
+            }
+            Node::Interior {
+                left,
+                right,
+                aabb,
+                axis,
+            } => {
+                self.nodes.push(LinearNode {
+                    aabb,
+                    offset: 0,
+                    count: 0,
+                    axis,
+                });
+                self.flatten(*left, depth + 1, index);
+                self.nodes[index].offset = self.flatten(*right, depth + 1, index);
+            }
+        }
+        index
+    }
+
+    fn cost(&self, index: usize) -> f64 {
+        let node = &self.nodes[index];
+        if node.count > 0 {
+            return node.count as f64;
         }
+
+        let (left, right) = (index + 1, node.offset);
+        let area = node.aabb.surface_area();
+        if area <= 0.0 {
+            return TRAVERSAL_COST + self.cost(left) + self.cost(right);
+        }
+        TRAVERSAL_COST
+            + (self.nodes[left].aabb.surface_area() * self.cost(left)
+                + self.nodes[right].aabb.surface_area() * self.cost(right))
+                / area
     }
 }
 
 impl Hittable for BVH {
     fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
-        self.root.hit(ray, t_range)
+        if self.nodes.is_empty() {
+            return Option::None;
+        }
+
+        let inv_dir = Vec3(1.0 / ray.dir.x(), 1.0 / ray.dir.y(), 1.0 / ray.dir.z());
+        let dir_is_neg = [inv_dir.x() < 0.0, inv_dir.y() < 0.0, inv_dir.z() < 0.0];
+
+        let mut result = Option::None;
+        let mut closest_so_far = t_range.1;
+        let mut stack = [0; MAX_DEPTH];
+        let mut stack_len = 0;
+        let mut current = 0;
+        loop {
+            let node = &self.nodes[current];
+            let range = (t_range.0, closest_so_far);
+            if node.aabb.hit_inverse(ray.orig, inv_dir, range).is_some() {
+                if node.count > 0 {
+                    for object in &self.objects[node.offset..node.offset + node.count] {
+                        if let Some(record) = object.hit(ray, (t_range.0, closest_so_far)) {
+                            closest_so_far = record.t;
+                            result = Option::Some(record);
+                        }
+                    }
+                } else {
+                    // 沿划分轴先访问离光线起点较近的子节点，较远的入栈
+                    let (near, far) = match dir_is_neg[node.axis] {
+                        true => (node.offset, current + 1),
+                        false => (current + 1, node.offset),
+                    };
+                    stack[stack_len] = far;
+                    stack_len += 1;
+                    current = near;
+                    continue;
+                }
+            }
+
+            if stack_len == 0 {
+                break;
+            }
+            stack_len -= 1;
+            current = stack[stack_len];
+        }
+        result
     }
 }
 
-fn recursive_build(mut list: BoundedList) -> Node {
-    let length = list.len();
-    if length == 1 {
-        return Node {
-            left: list[0].clone(),
-            right: list[0].clone(),
-            aabb: list[0].bounding_box(),
-        };
-    } else if length == 2 {
-        return Node {
-            left: list[0].clone(),
-            right: list[1].clone(),
-            aabb: list[0].bounding_box() + list[1].bounding_box(),
-        };
+// 使 BVH 本身也能作为物体加入场景，用于网格实例化
+impl Bounded for BVH {
+    fn bounding_box(&self) -> AABB {
+        self.nodes.first().map_or(AABB::default(), |root| root.aabb)
     }
+}
+
+// 构建期间缓存的图元包围盒与中心，`index` 为物体在构建输入中的下标
+struct Primitive {
+    object: BoundedObject,
+    index: usize,
+    aabb: AABB,
+    centroid: Point3,
+}
 
-    let axis = random_int(0, 2);
+impl Primitive {
+    fn new(index: usize, object: BoundedObject) -> Primitive {
+        let aabb = object.bounding_box();
+        Primitive {
+            object,
+            index,
+            aabb,
+            centroid: aabb.centroid(),
+        }
+    }
+}
 
-    list.sort_by_key(|key| key.bounding_box().min().get(axis as usize) as i32);
+// 构建期间的树，叶节点以下标区间引用就地重排后的图元
+enum Node {
+    Leaf {
+        start: usize,
+        count: usize,
+        aabb: AABB,
+    },
+    Interior {
+        left: Box<Node>,
+        right: Box<Node>,
+        aabb: AABB,
+        axis: usize,
+    },
+}
 
-    let (left, right) = list.split_at_mut(length / 2);
+// 求各图元的包围盒，图元较多时分块并行
+fn collect_primitives(objects: BoundedList) -> Vec<Primitive> {
+    objects
+        .into_par_iter()
+        .enumerate()
+        .with_min_len(PARALLEL_THRESHOLD)
+        .map(|(index, object)| Primitive::new(index, object))
+        .collect()
+}
 
-    let left_node = recursive_build(left.to_vec());
-    let right_node = recursive_build(right.to_vec());
-    let aabb = left_node.bounding_box() + right_node.bounding_box();
+fn bounds_of(primitives: &[Primitive]) -> AABB {
+    primitives[1..]
+        .iter()
+        .fold(primitives[0].aabb, |result, primitive| {
+            result + primitive.aabb
+        })
+}
 
-    let result = Node {
-        left: Arc::new(Box::new(left_node)),
-        right: Arc::new(Box::new(right_node)),
+// `start` 为 `primitives` 在整个图元数组中的起始下标，递归深度不超过 `MAX_DEPTH`
+fn build_node(
+    primitives: &mut [Primitive],
+    start: usize,
+    depth: usize,
+    method: SplitMethod,
+) -> Node {
+    let aabb = bounds_of(primitives);
+    let leaf = Node::Leaf {
+        start,
+        count: primitives.len(),
         aabb,
     };
+    if depth + 1 >= MAX_DEPTH {
+        return leaf;
+    }
 
-    result
+    let split = match method {
+        SplitMethod::Sah => split_sah(primitives, aabb),
+        SplitMethod::Median => split_median(primitives, aabb),
+    };
+    match split {
+        Some((mid, axis)) => {
+            let (left, right) = primitives.split_at_mut(mid);
+            let parallel = left.len().min(right.len()) >= PARALLEL_THRESHOLD;
+            let (left, right) = match parallel {
+                true => rayon::join(
+                    || build_node(left, start, depth + 1, method),
+                    || build_node(right, start + mid, depth + 1, method),
+                ),
+                false => (
+                    build_node(left, start, depth + 1, method),
+                    build_node(right, start + mid, depth + 1, method),
+                ),
+            };
+            Node::Interior {
+                left: Box::new(left),
+                right: Box::new(right),
+                aabb,
+                axis,
+            }
+        }
+        None => leaf,
+    }
 }
 
-struct Node {
-    left: BoundedObject,
-    right: BoundedObject,
-    aabb: AABB,
+fn bin_index(value: f64, low: f64, high: f64) -> usize {
+    (((value - low) / (high - low) * SAH_BINS as f64) as usize).min(SAH_BINS - 1)
 }
 
-impl Hittable for Node {
-    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
-        let range = self.aabb.hit(ray, t_range)?;
-
-        let mut left_result = self.left.hit(ray, range);
-        let right_result = self.right.hit(
-            ray,
-            match left_result {
-                Some(temp_result) => {
-                    let t = temp_result.t;
-                    left_result = Option::Some(temp_result);
-                    (range.0, t)
-                }
-                None => range,
-            },
+// 就地划分图元，返回左侧的图元数与划分轴；应当生成叶节点时返回 `None`
+fn split_sah(primitives: &mut [Primitive], aabb: AABB) -> Option<(usize, usize)> {
+    let length = primitives.len();
+    if length == 1 {
+        return Option::None;
+    }
+
+    let first = primitives[0].centroid;
+    let centroid_bounds = primitives[1..]
+        .iter()
+        .fold(AABB::new(first, first), |result, primitive| {
+            result + AABB::new(primitive.centroid, primitive.centroid)
+        });
+    let parent_area = aabb.surface_area();
+
+    // (代价, 轴, 左侧包含的最后一个桶)
+    let mut best: Option<(f64, usize, usize)> = None;
+    for axis in 0..3 {
+        let (low, high) = (
+            centroid_bounds.min().get(axis),
+            centroid_bounds.max().get(axis),
         );
+        if high <= low {
+            continue;
+        }
+
+        let mut bins: [(usize, Option<AABB>); SAH_BINS] = [(0, None); SAH_BINS];
+        for primitive in primitives.iter() {
+            let bin = &mut bins[bin_index(primitive.centroid.get(axis), low, high)];
+            bin.0 += 1;
+            bin.1 = Some(bin.1.map_or(primitive.aabb, |aabb| aabb + primitive.aabb));
+        }
 
-        match right_result {
-            Some(_) => right_result,
-            None => left_result,
+        // 从右向左累计每个划分右侧的图元数与表面积
+        let mut right_costs = [0.0; SAH_BINS];
+        let (mut count, mut bounds) = (0, None::<AABB>);
+        for split in (1..SAH_BINS).rev() {
+            count += bins[split].0;
+            bounds = union(bounds, bins[split].1);
+            right_costs[split - 1] = count as f64 * bounds.map_or(0.0, |b| b.surface_area());
         }
+
+        let (mut count, mut bounds) = (0, None::<AABB>);
+        for split in 0..SAH_BINS - 1 {
+            count += bins[split].0;
+            bounds = union(bounds, bins[split].1);
+            let left_cost = count as f64 * bounds.map_or(0.0, |b| b.surface_area());
+            let cost = TRAVERSAL_COST + (left_cost + right_costs[split]) / parent_area;
+            if count > 0 && count < length && best.is_none_or(|(best, _, _)| cost < best) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+
+    match best {
+        Some((cost, axis, split)) if length > MAX_LEAF_SIZE || cost < length as f64 => {
+            let (low, high) = (
+                centroid_bounds.min().get(axis),
+                centroid_bounds.max().get(axis),
+            );
+            let mut mid = 0;
+            for i in 0..length {
+                if bin_index(primitives[i].centroid.get(axis), low, high) <= split {
+                    primitives.swap(i, mid);
+                    mid += 1;
+                }
+            }
+            Some((mid, axis))
+        }
+        Some(_) => None,
+        // 所有中心重合，无法按位置区分，只能按数量对半划分
+        None if length > MAX_LEAF_SIZE => Some((length / 2, 0)),
+        None => None,
     }
 }
 
-impl Bounded for Node {
-    fn bounding_box(&self) -> AABB {
-        self.aabb
+fn union(lhs: Option<AABB>, rhs: Option<AABB>) -> Option<AABB> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+        (lhs, None) => lhs,
+        (None, rhs) => rhs,
     }
 }
+
+fn split_median(primitives: &mut [Primitive], aabb: AABB) -> Option<(usize, usize)> {
+    let length = primitives.len();
+    if length <= 2 {
+        return None;
+    }
+
+    let extent = aabb.max() - aabb.min();
+    let axis = (0..3)
+        .max_by(|&a, &b| extent.get(a).total_cmp(&extent.get(b)))
+        .unwrap_or_default();
+    primitives.sort_by(|a, b| a.aabb.min().get(axis).total_cmp(&b.aabb.min().get(axis)));
+    Some((length / 2, axis))
+}
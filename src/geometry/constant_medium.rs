@@ -0,0 +1,67 @@
+use crate::*;
+
+/// 以任意封闭物体为边界、密度均匀的参与介质（烟、雾）
+pub struct ConstantMedium {
+    boundary: ObjectType,
+    neg_inv_density: f64,
+    phase_function: MaterialType,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: ObjectType, density: f64, albedo: Color) -> ConstantMedium {
+        ConstantMedium::with_phase_function(
+            boundary,
+            density,
+            Arc::new(Box::new(Isotropic::new(albedo))),
+        )
+    }
+
+    pub fn with_phase_function(
+        boundary: ObjectType,
+        density: f64,
+        phase_function: MaterialType,
+    ) -> ConstantMedium {
+        ConstantMedium {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    // 求出光线在边界内的区间，按指数分布采样自由程，超出区间则穿过介质
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let enter = self.boundary.hit(ray, (f64::NEG_INFINITY, f64::INFINITY))?;
+        let exit = self.boundary.hit(ray, (enter.t + 1e-4, f64::INFINITY))?;
+
+        let t_enter = enter.t.max(t_range.0).max(0.0);
+        let t_exit = exit.t.min(t_range.1);
+        if t_enter >= t_exit {
+            return Option::None;
+        }
+
+        let ray_length = ray.dir.length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * random_01().ln();
+        if hit_distance > distance_inside {
+            return Option::None;
+        }
+
+        // 介质内部的散射点没有真实的表面，法线与朝向任意
+        let t = t_enter + hit_distance / ray_length;
+        Option::Some(HitRecord::new(
+            ray.at(t),
+            Vec3(1.0, 0.0, 0.0),
+            self.phase_function.clone(),
+            t,
+            true,
+        ))
+    }
+}
+
+impl Bounded for ConstantMedium {
+    fn bounding_box(&self) -> AABB {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,90 @@
+use crate::*;
+
+/// 密度由 `DensityGrid` 给出的非均匀参与介质，网格拉伸填满 `bounds`
+pub struct GridMedium {
+    grid: Arc<DensityGrid>,
+    bounds: AABB,
+    density_scale: f64,
+    phase_function: MaterialType,
+}
+
+impl GridMedium {
+    pub fn new(
+        grid: Arc<DensityGrid>,
+        bounds: AABB,
+        density_scale: f64,
+        phase_function: MaterialType,
+    ) -> GridMedium {
+        GridMedium {
+            grid,
+            bounds,
+            density_scale,
+            phase_function,
+        }
+    }
+
+    /// 世界坐标处的消光系数
+    pub fn density(&self, p: Point3) -> f64 {
+        let extent = self.bounds.max() - self.bounds.min();
+        let local = p - self.bounds.min();
+        let local = Vec3(
+            local.x() / extent.x(),
+            local.y() / extent.y(),
+            local.z() / extent.z(),
+        );
+        self.grid.density(local) * self.density_scale
+    }
+
+    fn majorant(&self) -> f64 {
+        self.grid.max_density() * self.density_scale
+    }
+
+    // 光线在包围盒内的参数区间
+    fn overlap(&self, ray: &Ray, t_range: (f64, f64)) -> Option<(f64, f64)> {
+        let (t0, t1) = self.bounds.hit(ray, (t_range.0.max(0.0), t_range.1))?;
+        match t0 < t1 {
+            true => Option::Some((t0, t1)),
+            false => Option::None,
+        }
+    }
+
+    // 以最大密度为上界按指数分布前进到下一个候选碰撞点
+    fn step(&self, t: f64, majorant: f64, ray_length: f64) -> f64 {
+        t - (1.0 - random_01()).ln() / (majorant * ray_length)
+    }
+}
+
+impl Hittable for GridMedium {
+    // 增量追踪（delta tracking）：在候选碰撞点以 密度/最大密度 的概率发生真实碰撞
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let majorant = self.majorant();
+        if majorant <= 0.0 {
+            return Option::None;
+        }
+        let (mut t, t_exit) = self.overlap(ray, t_range)?;
+
+        let ray_length = ray.dir.length();
+        loop {
+            t = self.step(t, majorant, ray_length);
+            if t >= t_exit {
+                return Option::None;
+            }
+            let hit_point = ray.at(t);
+            if random_01() * majorant < self.density(hit_point) {
+                return Option::Some(HitRecord::new(
+                    hit_point,
+                    Vec3(1.0, 0.0, 0.0),
+                    self.phase_function.clone(),
+                    t,
+                    true,
+                ));
+            }
+        }
+    }
+}
+
+impl Bounded for GridMedium {
+    fn bounding_box(&self) -> AABB {
+        self.bounds
+    }
+}
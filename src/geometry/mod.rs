@@ -1,3 +1,9 @@
 pub mod aabb;
 pub mod bvh;
+pub mod constant_medium;
+pub mod grid_medium;
+pub mod moving_sphere;
+pub mod rect;
 pub mod sphere;
+pub mod transform;
+pub mod triangle;
@@ -0,0 +1,50 @@
+use crate::*;
+
+/// 球心在 `time_range` 内从 `center0` 线性移动到 `center1` 的球体
+pub struct MovingSphere {
+    sphere: Sphere,
+    center0: Point3,
+    center1: Point3,
+    time_range: (f64, f64),
+}
+
+impl MovingSphere {
+    pub fn new(
+        center0: Point3,
+        center1: Point3,
+        time_range: (f64, f64),
+        radius: f64,
+        material: MaterialType,
+    ) -> MovingSphere {
+        MovingSphere {
+            sphere: Sphere::new(center0, radius, material),
+            center0,
+            center1,
+            time_range,
+        }
+    }
+
+    pub fn center(&self, time: f64) -> Point3 {
+        let duration = self.time_range.1 - self.time_range.0;
+        if duration <= 0.0 {
+            return self.center0;
+        }
+        let s = ((time - self.time_range.0) / duration).clamp(0.0, 1.0);
+        self.center0 + (self.center1 - self.center0) * s
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        self.sphere.hit_at(self.center(ray.time), ray, t_range)
+    }
+}
+
+impl Bounded for MovingSphere {
+    fn bounding_box(&self) -> AABB {
+        let r = self.sphere.radius().abs();
+        let r = Vec3(r, r, r);
+        AABB::new(self.center0 - r, self.center0 + r)
+            + AABB::new(self.center1 - r, self.center1 + r)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::*;
+
+/// 与坐标轴对齐的矩形，`axis` 为法线所在的轴，矩形位于 `axis = k` 平面上
+pub struct AxisAlignedRect {
+    axis: usize,
+    range_0: (f64, f64),
+    range_1: (f64, f64),
+    k: f64,
+    material: MaterialType,
+}
+
+impl AxisAlignedRect {
+    fn new(
+        axis: usize,
+        range_0: (f64, f64),
+        range_1: (f64, f64),
+        k: f64,
+        material: MaterialType,
+    ) -> AxisAlignedRect {
+        AxisAlignedRect {
+            axis,
+            range_0: (range_0.0.min(range_0.1), range_0.0.max(range_0.1)),
+            range_1: (range_1.0.min(range_1.1), range_1.0.max(range_1.1)),
+            k,
+            material,
+        }
+    }
+
+    /// 位于 z = k 平面上的矩形
+    pub fn xy(x: (f64, f64), y: (f64, f64), k: f64, material: MaterialType) -> AxisAlignedRect {
+        AxisAlignedRect::new(2, x, y, k, material)
+    }
+
+    /// 位于 y = k 平面上的矩形
+    pub fn xz(x: (f64, f64), z: (f64, f64), k: f64, material: MaterialType) -> AxisAlignedRect {
+        AxisAlignedRect::new(1, x, z, k, material)
+    }
+
+    /// 位于 x = k 平面上的矩形
+    pub fn yz(y: (f64, f64), z: (f64, f64), k: f64, material: MaterialType) -> AxisAlignedRect {
+        AxisAlignedRect::new(0, y, z, k, material)
+    }
+
+    fn tangent_axes(&self) -> (usize, usize) {
+        match self.axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        }
+    }
+}
+
+impl Hittable for AxisAlignedRect {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let (a, b) = self.tangent_axes();
+
+        // 光线与矩形平行
+        let denom = ray.dir.get(self.axis);
+        if denom.abs() < 1e-8 {
+            return Option::None;
+        }
+
+        let t = (self.k - ray.orig.get(self.axis)) / denom;
+        if !(t_range.0..=t_range.1).contains(&t) {
+            return Option::None;
+        }
+
+        let hit_point = ray.at(t);
+        let (p0, p1) = (hit_point.get(a), hit_point.get(b));
+        if p0 < self.range_0.0 || p0 > self.range_0.1 || p1 < self.range_1.0 || p1 > self.range_1.1
+        {
+            return Option::None;
+        }
+
+        let mut outward_normal = [0.0; 3];
+        outward_normal[self.axis] = 1.0;
+        let (front_face, hit_normal) =
+            Vec3::set_face_normal(ray.dir, Vec3::from_array(outward_normal));
+
+        let u = (p0 - self.range_0.0) / (self.range_0.1 - self.range_0.0);
+        let v = (p1 - self.range_1.0) / (self.range_1.1 - self.range_1.0);
+        let (mut dpdu, mut dpdv) = ([0.0; 3], [0.0; 3]);
+        dpdu[a] = 1.0;
+        dpdv[b] = 1.0;
+
+        Option::Some(
+            HitRecord::new(hit_point, hit_normal, self.material.clone(), t, front_face)
+                .with_uv(u, v)
+                .with_tangents(Vec3::from_array(dpdu), Vec3::from_array(dpdv)),
+        )
+    }
+}
+
+impl Bounded for AxisAlignedRect {
+    fn bounding_box(&self) -> AABB {
+        let (a, b) = self.tangent_axes();
+
+        let mut min = [0.0; 3];
+        let mut max = [0.0; 3];
+        (min[a], max[a]) = self.range_0;
+        (min[b], max[b]) = self.range_1;
+        (min[self.axis], max[self.axis]) = (self.k, self.k);
+
+        AABB::new(Vec3::from_array(min), Vec3::from_array(max)).pad()
+    }
+}
+
+/// 任意朝向的平行四边形，由角点 `q` 与两条边 `u`、`v` 确定
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    d: f64,
+    w: Vec3,
+    material: MaterialType,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, material: MaterialType) -> Quad {
+        let n = Vec3::cross(u, v);
+        let normal = n.unit_vector();
+        Quad {
+            q,
+            u,
+            v,
+            normal,
+            d: Vec3::dot(normal, q),
+            w: n / Vec3::dot(n, n),
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let denom = Vec3::dot(self.normal, ray.dir);
+        if denom.abs() < 1e-8 {
+            return Option::None;
+        }
+
+        let t = (self.d - Vec3::dot(self.normal, ray.orig)) / denom;
+        if !(t_range.0..=t_range.1).contains(&t) {
+            return Option::None;
+        }
+
+        // 求交点在 (u, v) 基下的平面坐标
+        let hit_point = ray.at(t);
+        let planar = hit_point - self.q;
+        let alpha = Vec3::dot(self.w, Vec3::cross(planar, self.v));
+        let beta = Vec3::dot(self.w, Vec3::cross(self.u, planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return Option::None;
+        }
+
+        let (front_face, hit_normal) = Vec3::set_face_normal(ray.dir, self.normal);
+
+        Option::Some(
+            HitRecord::new(hit_point, hit_normal, self.material.clone(), t, front_face)
+                .with_uv(alpha, beta)
+                .with_tangents(self.u, self.v),
+        )
+    }
+}
+
+impl Bounded for Quad {
+    fn bounding_box(&self) -> AABB {
+        let corners = [
+            self.q,
+            self.q + self.u,
+            self.q + self.v,
+            self.q + self.u + self.v,
+        ];
+        corners[1..]
+            .iter()
+            .fold(AABB::new(corners[0], corners[0]), |aabb, corner| {
+                aabb + AABB::new(*corner, *corner)
+            })
+            .pad()
+    }
+}
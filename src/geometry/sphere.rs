@@ -1,3 +1,5 @@
+use std::f64::consts::PI;
+
 use crate::*;
 
 pub struct Sphere {
@@ -14,11 +16,19 @@ impl Sphere {
             material,
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
-        let oc = ray.orig - self.center;
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    // 以给定的球心求交，供运动球体复用
+    pub(crate) fn hit_at(
+        &self,
+        center: Point3,
+        ray: &Ray,
+        t_range: (f64, f64),
+    ) -> Option<HitRecord> {
+        let oc = ray.orig - center;
         let a = ray.dir.length_squared();
         let half_b = Vec3::dot(oc, ray.dir);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -38,24 +48,55 @@ impl Hittable for Sphere {
         }
 
         let hit_point = ray.at(root);
-        let outward_normal = (hit_point - self.center) / self.radius;
+        let outward_normal = (hit_point - center) / self.radius;
         let (front_face, hit_normal) = Vec3::set_face_normal(ray.dir, outward_normal);
 
-        Option::Some(HitRecord::new(
-            ray.at(root),
-            hit_normal,
-            self.material.clone(),
-            root,
-            front_face,
-        ))
+        let (u, v) = sphere_uv(outward_normal);
+        let (dpdu, dpdv) = sphere_tangents(outward_normal);
+
+        Option::Some(
+            HitRecord::new(
+                ray.at(root),
+                hit_normal,
+                self.material.clone(),
+                root,
+                front_face,
+            )
+            .with_uv(u, v)
+            .with_tangents(dpdu, dpdv),
+        )
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        self.hit_at(self.center, ray, t_range)
     }
 }
 
+// 单位球面上一点的球面坐标：u 绕 y 轴从 -x 方向起算，v 从南极到北极
+fn sphere_uv(p: Point3) -> (f64, f64) {
+    let theta = (-p.y()).clamp(-1.0, 1.0).acos();
+    let phi = f64::atan2(-p.z(), p.x()) + PI;
+    (phi / (2.0 * PI), theta / PI)
+}
+
+// 单位球面上一点沿 u、v 增大方向的切向量（未归一化），两极处退化为零
+fn sphere_tangents(p: Point3) -> (Vec3, Vec3) {
+    (
+        Vec3(p.z(), 0.0, -p.x()),
+        Vec3(
+            -p.x() * p.y(),
+            p.x() * p.x() + p.z() * p.z(),
+            -p.y() * p.z(),
+        ),
+    )
+}
+
 impl Bounded for Sphere {
     fn bounding_box(&self) -> AABB {
-        AABB::new(
-            self.center - Vec3(self.radius, self.radius, self.radius),
-            self.center + Vec3(self.radius, self.radius, self.radius),
-        )
+        // 半径为负的球（空心玻璃球的内表面）同样要得到有效的包围盒
+        let r = self.radius.abs();
+        AABB::new(self.center - Vec3(r, r, r), self.center + Vec3(r, r, r))
     }
 }
@@ -0,0 +1,201 @@
+use crate::*;
+
+/// 对任意物体施加仿射变换，多个 `Transform` 可共享同一个物体实现实例化
+pub struct Transform {
+    object: ObjectType,
+    matrix: Mat4,
+    inverse: Mat4,
+    // 法线变换矩阵 (M⁻¹)ᵀ
+    normal_matrix: Mat4,
+    aabb: AABB,
+}
+
+impl Transform {
+    /// 变换矩阵不可逆时返回 `None`
+    pub fn new(object: ObjectType, matrix: Mat4) -> Option<Transform> {
+        let inverse = matrix.inverse()?;
+        let aabb = matrix.transform_aabb(object.bounding_box());
+        Some(Transform {
+            object,
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+            aabb,
+        })
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        self.matrix
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        // 方向不归一化，物体空间中的 t 与世界空间一致
+        let local_ray = Ray::new(
+            self.inverse.transform_point(ray.orig),
+            self.inverse.transform_vector(ray.dir),
+            ray.depth,
+        )
+        .with_time(ray.time);
+
+        let mut record = self.object.hit(&local_ray, t_range)?;
+        record.hit_point = self.matrix.transform_point(record.hit_point);
+        record.hit_normal = self
+            .normal_matrix
+            .transform_vector(record.hit_normal)
+            .unit_vector();
+        if !record.tangent.near_zero() {
+            record.tangent = self.matrix.transform_vector(record.tangent).unit_vector();
+            record.bitangent = self.matrix.transform_vector(record.bitangent).unit_vector();
+        }
+        Some(record)
+    }
+}
+
+impl Bounded for Transform {
+    fn bounding_box(&self) -> AABB {
+        self.aabb
+    }
+}
+
+/// 平移、旋转（绕 x/y/z 轴的角度）与缩放组成的位姿，按缩放、旋转、平移的顺序作用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub translate: Vec3,
+    pub rotate: Vec3,
+    pub scale: Vec3,
+}
+
+impl Default for Keyframe {
+    fn default() -> Self {
+        Keyframe {
+            translate: Vec3(0.0, 0.0, 0.0),
+            rotate: Vec3(0.0, 0.0, 0.0),
+            scale: Vec3(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl Keyframe {
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::translate(self.translate)
+            * Mat4::rotate_z(self.rotate.z())
+            * Mat4::rotate_y(self.rotate.y())
+            * Mat4::rotate_x(self.rotate.x())
+            * Mat4::scale(self.scale)
+    }
+
+    // 各分量逆序求逆，避免对一般矩阵做消元
+    fn inverse_matrix(&self) -> Mat4 {
+        Mat4::scale(Vec3(1.0, 1.0, 1.0) / self.scale)
+            * Mat4::rotate_x(-self.rotate.x())
+            * Mat4::rotate_y(-self.rotate.y())
+            * Mat4::rotate_z(-self.rotate.z())
+            * Mat4::translate(self.translate * -1.0)
+    }
+
+    pub fn lerp(&self, other: &Keyframe, s: f64) -> Keyframe {
+        Keyframe {
+            translate: self.translate + (other.translate - self.translate) * s,
+            rotate: self.rotate + (other.rotate - self.rotate) * s,
+            scale: self.scale + (other.scale - self.scale) * s,
+        }
+    }
+}
+
+/// 位姿在 `time_range` 内从 `start` 插值到 `end` 的变换，用于运动模糊
+pub struct MotionTransform {
+    object: ObjectType,
+    start: Keyframe,
+    end: Keyframe,
+    time_range: (f64, f64),
+    aabb: AABB,
+}
+
+// 计算运动范围包围盒时的时间采样数
+const MOTION_SAMPLES: usize = 32;
+
+impl MotionTransform {
+    /// 任一关键帧的缩放分量为零时返回 `None`
+    pub fn new(
+        object: ObjectType,
+        start: Keyframe,
+        end: Keyframe,
+        time_range: (f64, f64),
+    ) -> Option<MotionTransform> {
+        let degenerate = |keyframe: &Keyframe| keyframe.scale.array().contains(&0.0);
+        // 两帧缩放符号不同时中间必经过零
+        let flips = (0..3).any(|axis| start.scale.get(axis) * end.scale.get(axis) < 0.0);
+        if degenerate(&start) || degenerate(&end) || flips {
+            return None;
+        }
+
+        // 在时间上采样包围盒，并按相邻采样间角点的最大位移加厚，以覆盖旋转时的圆弧轨迹
+        let local = object.bounding_box();
+        let mut aabb = start.matrix().transform_aabb(local);
+        let mut max_step: f64 = 0.0;
+        let mut previous = aabb;
+        for i in 1..=MOTION_SAMPLES {
+            let keyframe = start.lerp(&end, i as f64 / MOTION_SAMPLES as f64);
+            let current = keyframe.matrix().transform_aabb(local);
+            max_step = max_step
+                .max((current.min() - previous.min()).length())
+                .max((current.max() - previous.max()).length());
+            aabb = aabb + current;
+            previous = current;
+        }
+        let padding = Vec3(max_step, max_step, max_step);
+        let aabb = AABB::new(aabb.min() - padding, aabb.max() + padding);
+
+        Some(MotionTransform {
+            object,
+            start,
+            end,
+            time_range,
+            aabb,
+        })
+    }
+
+    pub fn keyframe(&self, time: f64) -> Keyframe {
+        let duration = self.time_range.1 - self.time_range.0;
+        if duration <= 0.0 {
+            return self.start;
+        }
+        let s = ((time - self.time_range.0) / duration).clamp(0.0, 1.0);
+        self.start.lerp(&self.end, s)
+    }
+}
+
+impl Hittable for MotionTransform {
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let keyframe = self.keyframe(ray.time);
+        let matrix = keyframe.matrix();
+        let inverse = keyframe.inverse_matrix();
+
+        let local_ray = Ray::new(
+            inverse.transform_point(ray.orig),
+            inverse.transform_vector(ray.dir),
+            ray.depth,
+        )
+        .with_time(ray.time);
+
+        let mut record = self.object.hit(&local_ray, t_range)?;
+        record.hit_point = matrix.transform_point(record.hit_point);
+        record.hit_normal = inverse
+            .transpose()
+            .transform_vector(record.hit_normal)
+            .unit_vector();
+        if !record.tangent.near_zero() {
+            record.tangent = matrix.transform_vector(record.tangent).unit_vector();
+            record.bitangent = matrix.transform_vector(record.bitangent).unit_vector();
+        }
+        Some(record)
+    }
+}
+
+impl Bounded for MotionTransform {
+    fn bounding_box(&self) -> AABB {
+        self.aabb
+    }
+}
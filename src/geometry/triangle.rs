@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use crate::*;
+
+/// 三角形顶点在网格各缓冲区中的索引
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct VertexIndex {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub uv: Option<usize>,
+}
+
+/// 共享顶点、法线与纹理坐标缓冲区的三角形网格
+#[derive(Default)]
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+    triangles: Vec<[VertexIndex; 3]>,
+    // 每个三角形所属的材质分组（`usemtl`）
+    groups: Vec<Option<usize>>,
+    group_names: Vec<String>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Point3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<(f64, f64)>,
+        triangles: Vec<[VertexIndex; 3]>,
+    ) -> TriangleMesh {
+        let groups = vec![None; triangles.len()];
+        TriangleMesh {
+            positions,
+            normals,
+            uvs,
+            triangles,
+            groups,
+            group_names: Vec::new(),
+        }
+    }
+
+    pub fn with_groups(
+        mut self,
+        groups: Vec<Option<usize>>,
+        group_names: Vec<String>,
+    ) -> TriangleMesh {
+        assert_eq!(groups.len(), self.triangles.len());
+        self.groups = groups;
+        self.group_names = group_names;
+        self
+    }
+
+    pub fn positions(&self) -> &[Point3] {
+        &self.positions
+    }
+
+    pub fn normals(&self) -> &[Vec3] {
+        &self.normals
+    }
+
+    pub fn uvs(&self) -> &[(f64, f64)] {
+        &self.uvs
+    }
+
+    pub fn triangles(&self) -> &[[VertexIndex; 3]] {
+        &self.triangles
+    }
+
+    pub fn group_names(&self) -> &[String] {
+        &self.group_names
+    }
+
+    /// 三角形所属的材质分组名
+    pub fn group(&self, triangle: usize) -> Option<&str> {
+        self.groups[triangle].map(|group| self.group_names[group].as_str())
+    }
+
+    /// 将网格拆分为可加入 `Scene` 的三角形，按分组名在 `materials` 中查找材质，找不到时使用 `default`
+    pub fn objects(
+        self: &Arc<Self>,
+        default: MaterialType,
+        materials: &HashMap<String, MaterialType>,
+    ) -> Vec<ObjectType> {
+        (0..self.triangles.len())
+            .map(|index| {
+                let material = self
+                    .group(index)
+                    .and_then(|name| materials.get(name))
+                    .unwrap_or(&default)
+                    .clone();
+                let object: ObjectType =
+                    Arc::new(Box::new(Triangle::new(self.clone(), index, material)));
+                object
+            })
+            .collect()
+    }
+}
+
+pub struct Triangle {
+    mesh: Arc<TriangleMesh>,
+    index: usize,
+    material: MaterialType,
+}
+
+impl Triangle {
+    pub fn new(mesh: Arc<TriangleMesh>, index: usize, material: MaterialType) -> Triangle {
+        Triangle {
+            mesh,
+            index,
+            material,
+        }
+    }
+
+    fn vertices(&self) -> [Point3; 3] {
+        self.mesh.triangles[self.index].map(|vertex| self.mesh.positions[vertex.position])
+    }
+}
+
+impl Hittable for Triangle {
+    // Möller–Trumbore 求交
+    fn hit(&self, ray: &Ray, t_range: (f64, f64)) -> Option<HitRecord> {
+        let [p0, p1, p2] = self.vertices();
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+
+        let pvec = Vec3::cross(ray.dir, edge2);
+        let det = Vec3::dot(edge1, pvec);
+        if det.abs() < 1e-12 {
+            return Option::None;
+        }
+        let inv_det = 1.0 / det;
+
+        let tvec = ray.orig - p0;
+        let b1 = Vec3::dot(tvec, pvec) * inv_det;
+        if !(0.0..=1.0).contains(&b1) {
+            return Option::None;
+        }
+
+        let qvec = Vec3::cross(tvec, edge1);
+        let b2 = Vec3::dot(ray.dir, qvec) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return Option::None;
+        }
+
+        let t = Vec3::dot(edge2, qvec) * inv_det;
+        if t < t_range.0 || t_range.1 < t {
+            return Option::None;
+        }
+
+        let b0 = 1.0 - b1 - b2;
+        let indices = self.mesh.triangles[self.index];
+        let outward_normal = match indices.map(|vertex| vertex.normal) {
+            [Some(n0), Some(n1), Some(n2)] => (self.mesh.normals[n0] * b0
+                + self.mesh.normals[n1] * b1
+                + self.mesh.normals[n2] * b2)
+                .unit_vector(),
+            _ => Vec3::cross(edge1, edge2).unit_vector(),
+        };
+        let (front_face, hit_normal) = Vec3::set_face_normal(ray.dir, outward_normal);
+
+        // 没有纹理坐标时以重心坐标作为 (u, v)
+        let ((u, v), (dpdu, dpdv)) = match indices.map(|vertex| vertex.uv) {
+            [Some(t0), Some(t1), Some(t2)] => {
+                let [uv0, uv1, uv2] = [t0, t1, t2].map(|index| self.mesh.uvs[index]);
+                let uv = (
+                    uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2,
+                    uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2,
+                );
+                (uv, uv_tangents(edge1, edge2, uv0, uv1, uv2))
+            }
+            _ => ((b1, b2), (edge1, edge2)),
+        };
+
+        Option::Some(
+            HitRecord::new(ray.at(t), hit_normal, self.material.clone(), t, front_face)
+                .with_uv(u, v)
+                .with_tangents(dpdu, dpdv),
+        )
+    }
+}
+
+// 由两条边与对应的纹理坐标差求解 dp/du、dp/dv，纹理坐标退化时返回零向量
+fn uv_tangents(
+    edge1: Vec3,
+    edge2: Vec3,
+    uv0: (f64, f64),
+    uv1: (f64, f64),
+    uv2: (f64, f64),
+) -> (Vec3, Vec3) {
+    let (du1, dv1) = (uv1.0 - uv0.0, uv1.1 - uv0.1);
+    let (du2, dv2) = (uv2.0 - uv0.0, uv2.1 - uv0.1);
+    let det = du1 * dv2 - du2 * dv1;
+    if det.abs() < 1e-12 {
+        return (Vec3::default(), Vec3::default());
+    }
+
+    let inv_det = 1.0 / det;
+    (
+        (edge1 * dv2 - edge2 * dv1) * inv_det,
+        (edge2 * du1 - edge1 * du2) * inv_det,
+    )
+}
+
+impl Bounded for Triangle {
+    fn bounding_box(&self) -> AABB {
+        let [p0, p1, p2] = self.vertices();
+        (AABB::new(p0, p0) + AABB::new(p1, p1) + AABB::new(p2, p2)).pad()
+    }
+}
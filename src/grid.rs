@@ -0,0 +1,148 @@
+use std::{error::Error, fmt::Display, fs, path::Path};
+
+use crate::*;
+
+const GRID_MAGIC: &[u8; 4] = b"GRID";
+
+#[derive(Debug)]
+pub enum GridError {
+    Io(String, std::io::Error),
+    Invalid(String),
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Io(path, err) => write!(f, "could not read grid file `{}`: {}", path, err),
+            GridError::Invalid(message) => write!(f, "invalid grid file: {}", message),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+/// 稠密的三维密度网格，体素按 x、y、z 的顺序存储（x 变化最快）
+pub struct DensityGrid {
+    resolution: [usize; 3],
+    data: Vec<f32>,
+    max_density: f64,
+}
+
+impl DensityGrid {
+    pub fn new(resolution: [usize; 3], data: Vec<f32>) -> DensityGrid {
+        assert_eq!(data.len(), resolution.iter().product::<usize>());
+        let max_density = data.iter().fold(0.0f32, |max, value| max.max(*value)) as f64;
+        DensityGrid {
+            resolution,
+            data,
+            max_density,
+        }
+    }
+
+    pub fn resolution(&self) -> [usize; 3] {
+        self.resolution
+    }
+
+    pub fn max_density(&self) -> f64 {
+        self.max_density
+    }
+
+    pub fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        let [nx, ny, _] = self.resolution;
+        self.data[(z * ny + y) * nx + x] as f64
+    }
+
+    /// 以 [0, 1]^3 内的归一化坐标三线性插值取密度，体素值位于体素中心，网格外为零
+    pub fn density(&self, p: Vec3) -> f64 {
+        if p.array().iter().any(|c| !(0.0..=1.0).contains(c)) {
+            return 0.0;
+        }
+
+        let mut lower = [0; 3];
+        let mut upper = [0; 3];
+        let mut fraction = [0.0; 3];
+        for axis in 0..3 {
+            let n = self.resolution[axis];
+            let x = (p.get(axis) * n as f64 - 0.5).clamp(0.0, (n - 1) as f64);
+            lower[axis] = x.floor() as usize;
+            upper[axis] = (lower[axis] + 1).min(n - 1);
+            fraction[axis] = x - lower[axis] as f64;
+        }
+
+        let mut result = 0.0;
+        for corner in 0..8 {
+            let pick = |axis: usize| corner & (1 << axis) != 0;
+            let mut weight = 1.0;
+            let mut index = [0; 3];
+            for axis in 0..3 {
+                (index[axis], weight) = match pick(axis) {
+                    true => (upper[axis], weight * fraction[axis]),
+                    false => (lower[axis], weight * (1.0 - fraction[axis])),
+                };
+            }
+            if weight > 0.0 {
+                result += weight * self.voxel(index[0], index[1], index[2]);
+            }
+        }
+        result
+    }
+}
+
+/// 读取密度网格文件：4 字节魔数 `GRID`，三个小端 u32 分辨率，随后为小端 f32 体素值
+pub fn load_grid<P: AsRef<Path>>(path: P) -> Result<DensityGrid, GridError> {
+    let path = path.as_ref().display().to_string();
+    let bytes = fs::read(&path).map_err(|err| GridError::Io(path.clone(), err))?;
+    parse_grid(&bytes)
+}
+
+pub fn parse_grid(bytes: &[u8]) -> Result<DensityGrid, GridError> {
+    if bytes.len() < 16 || &bytes[..4] != GRID_MAGIC {
+        return Err(GridError::Invalid(String::from("missing `GRID` header")));
+    }
+
+    let word = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let resolution = [word(4) as usize, word(8) as usize, word(12) as usize];
+    if resolution.contains(&0) {
+        return Err(GridError::Invalid(String::from(
+            "resolution must be positive",
+        )));
+    }
+
+    let count = resolution
+        .iter()
+        .try_fold(1usize, |count, n| count.checked_mul(*n))
+        .ok_or_else(|| GridError::Invalid(String::from("resolution is too large")))?;
+    let body = &bytes[16..];
+    if Some(body.len()) != count.checked_mul(4) {
+        return Err(GridError::Invalid(format!(
+            "expected {} voxels, found {} bytes of data",
+            count,
+            body.len()
+        )));
+    }
+
+    let data: Vec<f32> = body
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    if data.iter().any(|value| !value.is_finite() || *value < 0.0) {
+        return Err(GridError::Invalid(String::from(
+            "densities must be finite and non-negative",
+        )));
+    }
+
+    Ok(DensityGrid::new(resolution, data))
+}
+
+/// 按 `load_grid` 读取的格式编码密度网格
+pub fn encode_grid(grid: &DensityGrid) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(16 + grid.data.len() * 4);
+    bytes.extend_from_slice(GRID_MAGIC);
+    for n in grid.resolution {
+        bytes.extend_from_slice(&(n as u32).to_le_bytes());
+    }
+    for value in &grid.data {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
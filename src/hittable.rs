@@ -12,6 +12,11 @@ pub struct HitRecord {
     pub hit_material: MaterialType,
     pub t: f64,
     pub front_face: bool,
+    pub u: f64,
+    pub v: f64,
+    /// 沿 u、v 增大方向的单位切向量，只由 `with_tangents` 设置，没有表面的击中（如介质）为零向量
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
 }
 
 impl HitRecord {
@@ -28,8 +33,29 @@ impl HitRecord {
             hit_material,
             t,
             front_face,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::default(),
+            bitangent: Vec3::default(),
         }
     }
+
+    /// 设置击中点的表面参数坐标
+    pub fn with_uv(mut self, u: f64, v: f64) -> HitRecord {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
+    /// 设置表面沿 u、v 增大方向的单位切向量，退化时（如球面两极）改用由法线生成的切线框架
+    pub fn with_tangents(mut self, dpdu: Vec3, dpdv: Vec3) -> HitRecord {
+        (self.tangent, self.bitangent) =
+            match !dpdu.near_zero() && !dpdv.near_zero() && !Vec3::cross(dpdu, dpdv).near_zero() {
+                true => (dpdu.unit_vector(), dpdv.unit_vector()),
+                false => Vec3::orthonormal_basis(self.hit_normal),
+            };
+        self
+    }
 }
 
 impl Display for HitRecord {
@@ -1,27 +1,69 @@
+mod background;
 mod camera;
 mod config;
+mod film;
+mod filter;
 mod geometry;
+mod grid;
 mod hittable;
+mod loader;
 mod material;
+mod matrix;
+mod obj;
+mod output;
 mod ray;
 mod renderer;
+mod sampler;
 mod scene;
+mod texture;
+mod tile;
+mod tonemap;
 mod utils;
 mod vec3;
 
+pub use crate::background::*;
 pub use crate::camera::*;
 pub use crate::config::*;
+pub use crate::film::*;
+pub use crate::filter::*;
 pub use crate::geometry::aabb::*;
 pub use crate::geometry::bvh::*;
+pub use crate::geometry::constant_medium::*;
+pub use crate::geometry::grid_medium::*;
+pub use crate::geometry::moving_sphere::*;
+pub use crate::geometry::rect::*;
 pub use crate::geometry::sphere::*;
+pub use crate::geometry::transform::*;
+pub use crate::geometry::triangle::*;
+pub use crate::grid::*;
 pub use crate::hittable::*;
+pub use crate::loader::*;
 pub use crate::material::dielectric::*;
+pub use crate::material::diffuse_light::*;
+pub use crate::material::henyey_greenstein::*;
+pub use crate::material::isotropic::*;
 pub use crate::material::lambertian::*;
 pub use crate::material::metal::*;
 pub use crate::material::*;
+pub use crate::matrix::*;
+pub use crate::obj::*;
+pub use crate::output::*;
 pub use crate::ray::*;
 pub use crate::renderer::*;
+pub use crate::sampler::blue_noise::*;
+pub use crate::sampler::halton::*;
+pub use crate::sampler::independent::*;
+pub use crate::sampler::sobol::*;
+pub use crate::sampler::stratified::*;
+pub use crate::sampler::*;
 pub use crate::scene::*;
+pub use crate::texture::checker::*;
+pub use crate::texture::image_texture::*;
+pub use crate::texture::noise::*;
+pub use crate::texture::solid::*;
+pub use crate::texture::*;
+pub use crate::tile::*;
+pub use crate::tonemap::*;
 pub use crate::utils::*;
 pub use crate::vec3::*;
 
@@ -30,40 +72,31 @@ use std::sync::Arc;
 
 pub type MaterialType = Arc<Box<dyn Material + Send + Sync>>;
 
+pub type TextureType = Arc<Box<dyn Texture + Send + Sync>>;
+
 pub type ObjectType = Arc<Box<dyn Bounded + Send + Sync>>;
 
+pub type BackgroundType = Arc<Box<dyn Background + Send + Sync>>;
+
 pub type ConfigType = Arc<Box<Config>>;
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    run_with_progress(config, |_| {})
+}
+
+/// 与 `run` 相同，渲染期间每完成一个区块以当前进度调用一次 `progress`
+pub fn run_with_progress<F: FnMut(&Progress) + 'static>(
+    config: Config,
+    progress: F,
+) -> Result<(), Box<dyn Error>> {
     let config = Arc::new(Box::new(config));
     let mut renderer = Renderer::new(config.clone());
+    renderer.set_progress(progress);
 
     println!("Running...");
-    for j in (0..=config.image_height - 1).rev() {
-        for i in 0..config.image_width {
-            let mut pixel_color = Color::new_color(0.0, 0.0, 0.0);
-            let sender = renderer.sender();
-            let config = config.clone();
-
-            renderer.threadpool().execute(move || {
-                for _ in 0..config.samples_per_pixel {
-                    let (u, v) = (
-                        (i as f64 + random_01()) / (config.image_width - 1) as f64,
-                        (j as f64 + random_01()) / (config.image_height - 1) as f64,
-                    );
-
-                    let ray = config.camera.get_ray_upper_left(u, v);
-
-                    pixel_color += ray_color(ray, &config);
-                }
-
-                let pixel = gamma_correct(pixel_color / config.samples_per_pixel as f64);
-                sender.send((i, j, pixel)).expect("Could not send pixel");
-            });
-        }
-    }
+    renderer.render()?;
 
-    renderer.save_png();
+    renderer.save()?;
     println!("Done.");
 
     Ok(())
@@ -0,0 +1,1026 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::Display,
+    fs,
+    path::Path,
+};
+
+use crate::*;
+
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(String, std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String, String),
+}
+
+impl SceneError {
+    fn invalid(entry: impl Into<String>, message: impl Into<String>) -> SceneError {
+        SceneError::Invalid(entry.into(), message.into())
+    }
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::Io(path, err) => write!(f, "could not read scene file `{}`: {}", path, err),
+            SceneError::Parse(err) => write!(f, "{}", err),
+            SceneError::Invalid(entry, message) => write!(f, "`{}`: {}", entry, message),
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default = "default_file_path")]
+    file_path: String,
+    camera: CameraDesc,
+    #[serde(default)]
+    film: FilmDesc,
+    background: Option<BackgroundDesc>,
+    #[serde(default)]
+    textures: BTreeMap<String, TextureDesc>,
+    #[serde(default)]
+    materials: BTreeMap<String, MaterialDesc>,
+    #[serde(default)]
+    shapes: BTreeMap<String, ObjectDesc>,
+    #[serde(default)]
+    objects: Vec<ObjectDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDesc {
+    look_from: [f64; 3],
+    look_at: [f64; 3],
+    #[serde(default = "default_view_up")]
+    view_up: [f64; 3],
+    #[serde(default = "default_field_of_view")]
+    field_of_view: f64,
+    #[serde(default)]
+    aperture: f64,
+    focus_distance: Option<f64>,
+    #[serde(default = "default_ray_depth")]
+    ray_depth: u32,
+    #[serde(default)]
+    shutter: [f64; 2],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilmDesc {
+    #[serde(default = "default_image_width")]
+    image_width: u32,
+    image_height: Option<u32>,
+    #[serde(default = "default_aspect_ratio")]
+    aspect_ratio: f64,
+    #[serde(default = "default_samples_per_pixel")]
+    samples_per_pixel: u32,
+    #[serde(default = "default_seed")]
+    seed: u64,
+    sampler: Option<String>,
+    filter: Option<String>,
+    filter_radius: Option<f64>,
+    tone_map: Option<String>,
+    #[serde(default)]
+    exposure: f64,
+    #[serde(default = "default_white_point")]
+    white_point: f64,
+}
+
+impl Default for FilmDesc {
+    fn default() -> Self {
+        FilmDesc {
+            image_width: default_image_width(),
+            image_height: None,
+            aspect_ratio: default_aspect_ratio(),
+            samples_per_pixel: default_samples_per_pixel(),
+            seed: default_seed(),
+            sampler: None,
+            filter: None,
+            filter_radius: None,
+            tone_map: None,
+            exposure: 0.0,
+            white_point: default_white_point(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundDesc {
+    Solid {
+        color: [f64; 3],
+    },
+    Gradient {
+        #[serde(default = "default_gradient_bottom")]
+        bottom: [f64; 3],
+        #[serde(default = "default_gradient_top")]
+        top: [f64; 3],
+    },
+    Environment {
+        path: String,
+        #[serde(default)]
+        rotation: f64,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+    },
+}
+
+/// 颜色既可以直接写成 RGB 数组，也可以引用 `textures` 中的纹理名
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorDesc {
+    Color([f64; 3]),
+    Texture(String),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum WrapDesc {
+    #[default]
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum FilterDesc {
+    Nearest,
+    #[default]
+    Bilinear,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum NoiseDesc {
+    #[default]
+    Perlin,
+    Turbulence,
+    Marble,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum TextureDesc {
+    Solid {
+        color: [f64; 3],
+    },
+    Checker {
+        odd: ColorDesc,
+        even: ColorDesc,
+        #[serde(default = "default_scale")]
+        scale: f64,
+    },
+    Image {
+        path: String,
+        #[serde(default)]
+        wrap: WrapDesc,
+        #[serde(default)]
+        filter: FilterDesc,
+    },
+    Noise {
+        #[serde(default)]
+        kind: NoiseDesc,
+        #[serde(default = "default_scale")]
+        scale: f64,
+        #[serde(default = "default_noise_color")]
+        color: [f64; 3],
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialDesc {
+    Lambertian {
+        albedo: ColorDesc,
+    },
+    Metal {
+        albedo: ColorDesc,
+        #[serde(default)]
+        fuzz: f64,
+    },
+    Dielectric {
+        ior: f64,
+    },
+    DiffuseLight {
+        emit: ColorDesc,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+    },
+    Isotropic {
+        albedo: ColorDesc,
+    },
+    HenyeyGreenstein {
+        albedo: ColorDesc,
+        #[serde(default)]
+        g: f64,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectDesc {
+    /// 指定 `center1` 时为运动球体，在时间 0 到 1 内从 `center` 移动到 `center1`
+    Sphere {
+        center: [f64; 3],
+        center1: Option<[f64; 3]>,
+        radius: f64,
+        material: String,
+    },
+    XyRect {
+        x: [f64; 2],
+        y: [f64; 2],
+        k: f64,
+        material: String,
+    },
+    XzRect {
+        x: [f64; 2],
+        z: [f64; 2],
+        k: f64,
+        material: String,
+    },
+    YzRect {
+        y: [f64; 2],
+        z: [f64; 2],
+        k: f64,
+        material: String,
+    },
+    Quad {
+        q: [f64; 3],
+        u: [f64; 3],
+        v: [f64; 3],
+        material: String,
+    },
+    Mesh {
+        path: String,
+        material: String,
+        #[serde(default)]
+        materials: BTreeMap<String, String>,
+    },
+    /// 引用 `shapes` 中的几何体，依次施加缩放、绕 x/y/z 轴旋转（角度）与平移
+    Instance {
+        shape: String,
+        #[serde(default)]
+        translate: [f64; 3],
+        #[serde(default)]
+        rotate: [f64; 3],
+        #[serde(default = "default_instance_scale")]
+        scale: ScaleDesc,
+        motion: Option<MotionDesc>,
+    },
+    /// 以 `shapes` 中的几何体为边界的均匀参与介质，`material` 为其相函数（通常为 `isotropic`）
+    ConstantMedium {
+        boundary: String,
+        density: f64,
+        material: String,
+    },
+    /// 从密度网格文件读取的非均匀介质，网格拉伸填满 `min` 与 `max` 围成的包围盒，
+    /// 体素值乘以 `density` 得到消光系数
+    GridMedium {
+        path: String,
+        min: [f64; 3],
+        max: [f64; 3],
+        #[serde(default = "default_density")]
+        density: f64,
+        material: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScaleDesc {
+    Uniform(f64),
+    Axes([f64; 3]),
+}
+
+impl ScaleDesc {
+    fn factors(&self) -> Vec3 {
+        match self {
+            ScaleDesc::Uniform(factor) => Vec3(*factor, *factor, *factor),
+            ScaleDesc::Axes(factors) => Vec3::from_array(*factors),
+        }
+    }
+}
+
+/// 实例在时间 1 时的位姿，省略的分量与初始位姿相同
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MotionDesc {
+    translate: Option<[f64; 3]>,
+    rotate: Option<[f64; 3]>,
+    scale: Option<ScaleDesc>,
+}
+
+fn default_file_path() -> String {
+    String::from("image.png")
+}
+
+fn default_gradient_bottom() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_gradient_top() -> [f64; 3] {
+    [0.5, 0.7, 1.0]
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_noise_color() -> [f64; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_instance_scale() -> ScaleDesc {
+    ScaleDesc::Uniform(1.0)
+}
+
+fn default_intensity() -> f64 {
+    1.0
+}
+
+fn default_density() -> f64 {
+    1.0
+}
+
+fn default_view_up() -> [f64; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_field_of_view() -> f64 {
+    20.0
+}
+
+fn default_ray_depth() -> u32 {
+    50
+}
+
+fn default_image_width() -> u32 {
+    1200
+}
+
+fn default_aspect_ratio() -> f64 {
+    3.0 / 2.0
+}
+
+fn default_seed() -> u64 {
+    DEFAULT_SEED
+}
+
+fn default_white_point() -> f64 {
+    DEFAULT_WHITE_POINT
+}
+
+fn default_samples_per_pixel() -> u32 {
+    500
+}
+
+/// 读取场景描述文件（TOML），构建完整的 `Config`（包括场景的 BVH）
+pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Config, SceneError> {
+    let base_dir = path.as_ref().parent().unwrap_or_else(|| Path::new("."));
+    let path = path.as_ref().display().to_string();
+    let text = fs::read_to_string(&path).map_err(|err| SceneError::Io(path.clone(), err))?;
+    parse_config_in(&text, base_dir)
+}
+
+/// 从 TOML 文本解析 `Config`，文件中的相对路径相对于当前目录
+pub fn parse_config(text: &str) -> Result<Config, SceneError> {
+    parse_config_in(text, Path::new("."))
+}
+
+fn parse_config_in(text: &str, base_dir: &Path) -> Result<Config, SceneError> {
+    let desc: SceneFile = toml::from_str(text).map_err(SceneError::Parse)?;
+
+    let film = &desc.film;
+    // 相机把像素坐标除以 (宽 - 1) 与 (高 - 1)，图像每个方向至少需要两个像素
+    if film.image_width < 2 {
+        return Err(SceneError::invalid(
+            "film.image_width",
+            "must be at least 2",
+        ));
+    }
+    if film.samples_per_pixel == 0 {
+        return Err(SceneError::invalid(
+            "film.samples_per_pixel",
+            "must be positive",
+        ));
+    }
+    let (image_height, aspect_ratio) = match film.image_height {
+        Some(height) if height < 2 => {
+            return Err(SceneError::invalid(
+                "film.image_height",
+                "must be at least 2",
+            ))
+        }
+        Some(height) => (height, film.image_width as f64 / height as f64),
+        None if film.aspect_ratio > 0.0 => (
+            (film.image_width as f64 / film.aspect_ratio) as u32,
+            film.aspect_ratio,
+        ),
+        None => return Err(SceneError::invalid("film.aspect_ratio", "must be positive")),
+    };
+    if image_height < 2 {
+        return Err(SceneError::invalid(
+            "film.image_height",
+            format!(
+                "{} pixels derived from image_width and aspect_ratio, must be at least 2",
+                image_height
+            ),
+        ));
+    }
+
+    let sampler = match &film.sampler {
+        Some(name) => name
+            .parse()
+            .map_err(|err| SceneError::invalid("film.sampler", err))?,
+        None => SamplerKind::default(),
+    };
+    let filter = build_filter(film)?;
+    let tone_mapping = build_tone_mapping(film)?;
+    let camera = build_camera(&desc.camera, aspect_ratio)?;
+    let mut scene = build_scene(&desc, base_dir)?;
+    if let Some(background) = &desc.background {
+        scene.set_background(build_background(background, base_dir)?);
+    }
+
+    Ok(Config {
+        file_path: desc.file_path,
+        camera,
+        image_width: film.image_width,
+        image_height,
+        samples_per_pixel: film.samples_per_pixel,
+        threads: num_cpus::get(),
+        seed: film.seed,
+        tile_size: DEFAULT_TILE_SIZE,
+        tile_order: TileOrder::default(),
+        progressive: false,
+        snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+        sampler,
+        filter,
+        tone_mapping,
+        scene,
+    })
+}
+
+fn build_filter(film: &FilmDesc) -> Result<Filter, SceneError> {
+    let kind = match &film.filter {
+        Some(name) => name
+            .parse()
+            .map_err(|err| SceneError::invalid("film.filter", err))?,
+        None => FilterKind::default(),
+    };
+    match film.filter_radius {
+        Some(radius) if radius.is_nan() || radius <= 0.0 || radius > MAX_FILTER_RADIUS => {
+            Err(SceneError::invalid(
+                "film.filter_radius",
+                format!("must be in (0, {}]", MAX_FILTER_RADIUS),
+            ))
+        }
+        Some(radius) => Ok(Filter::new(kind).with_radius(radius)),
+        None => Ok(Filter::new(kind)),
+    }
+}
+
+fn build_tone_mapping(film: &FilmDesc) -> Result<ToneMapping, SceneError> {
+    let operator = match &film.tone_map {
+        Some(name) => name
+            .parse()
+            .map_err(|err| SceneError::invalid("film.tone_map", err))?,
+        None => ToneMapper::default(),
+    };
+    if !film.exposure.is_finite() {
+        return Err(SceneError::invalid("film.exposure", "must be finite"));
+    }
+    if film.white_point.is_nan() || film.white_point <= 0.0 {
+        return Err(SceneError::invalid("film.white_point", "must be positive"));
+    }
+    Ok(ToneMapping {
+        operator,
+        exposure: film.exposure,
+        white_point: film.white_point,
+    })
+}
+
+fn build_camera(desc: &CameraDesc, aspect_ratio: f64) -> Result<Camera, SceneError> {
+    let look_from = Vec3::from_array(desc.look_from);
+    let look_at = Vec3::from_array(desc.look_at);
+    let view_up = Vec3::from_array(desc.view_up);
+
+    if (look_from - look_at).near_zero() {
+        return Err(SceneError::invalid(
+            "camera.look_at",
+            "must differ from camera.look_from",
+        ));
+    }
+    if Vec3::cross(view_up, look_from - look_at).near_zero() {
+        return Err(SceneError::invalid(
+            "camera.view_up",
+            "must not be parallel to the viewing direction",
+        ));
+    }
+    if desc.field_of_view <= 0.0 || desc.field_of_view >= 180.0 {
+        return Err(SceneError::invalid(
+            "camera.field_of_view",
+            "must be between 0 and 180 degrees",
+        ));
+    }
+    if desc.aperture < 0.0 {
+        return Err(SceneError::invalid(
+            "camera.aperture",
+            "must not be negative",
+        ));
+    }
+    let focus_distance = desc
+        .focus_distance
+        .unwrap_or_else(|| (look_from - look_at).length());
+    if focus_distance <= 0.0 {
+        return Err(SceneError::invalid(
+            "camera.focus_distance",
+            "must be positive",
+        ));
+    }
+    if desc.ray_depth == 0 {
+        return Err(SceneError::invalid("camera.ray_depth", "must be positive"));
+    }
+    if desc.shutter[0] > desc.shutter[1] {
+        return Err(SceneError::invalid(
+            "camera.shutter",
+            "must not close before it opens",
+        ));
+    }
+
+    Ok(Camera::new(
+        look_from,
+        look_at,
+        view_up,
+        desc.field_of_view,
+        aspect_ratio,
+        desc.aperture,
+        focus_distance,
+        desc.ray_depth,
+    )
+    .with_shutter(desc.shutter[0], desc.shutter[1]))
+}
+
+fn build_background(desc: &BackgroundDesc, base_dir: &Path) -> Result<BackgroundType, SceneError> {
+    let background: BackgroundType = match desc {
+        BackgroundDesc::Solid { color } => {
+            Arc::new(Box::new(SolidBackground::new(Color::from_array(*color))))
+        }
+        BackgroundDesc::Gradient { bottom, top } => Arc::new(Box::new(GradientBackground::new(
+            Color::from_array(*bottom),
+            Color::from_array(*top),
+        ))),
+        BackgroundDesc::Environment {
+            path,
+            rotation,
+            intensity,
+        } => {
+            if *intensity < 0.0 {
+                return Err(SceneError::invalid(
+                    "background",
+                    "intensity must not be negative",
+                ));
+            }
+            let map = EnvironmentMap::open(base_dir.join(path), *rotation, *intensity)
+                .map_err(|err| SceneError::invalid("background", format!("{}: {}", path, err)))?;
+            Arc::new(Box::new(map))
+        }
+    };
+    Ok(background)
+}
+
+struct TextureLibrary<'a> {
+    descs: &'a BTreeMap<String, TextureDesc>,
+    built: BTreeMap<String, TextureType>,
+    base_dir: &'a Path,
+}
+
+impl<'a> TextureLibrary<'a> {
+    fn build(
+        descs: &'a BTreeMap<String, TextureDesc>,
+        base_dir: &'a Path,
+    ) -> Result<TextureLibrary<'a>, SceneError> {
+        let mut library = TextureLibrary {
+            descs,
+            built: BTreeMap::new(),
+            base_dir,
+        };
+        for name in descs.keys() {
+            library.texture(&format!("textures.{}", name), name, &mut Vec::new())?;
+        }
+        Ok(library)
+    }
+
+    fn color(
+        &mut self,
+        entry: &str,
+        desc: &ColorDesc,
+        stack: &mut Vec<String>,
+    ) -> Result<TextureType, SceneError> {
+        match desc {
+            ColorDesc::Color(color) => Ok(Arc::new(Box::new(SolidColor::new(Color::from_array(
+                *color,
+            ))))),
+            ColorDesc::Texture(name) => self.texture(entry, name, stack),
+        }
+    }
+
+    fn texture(
+        &mut self,
+        entry: &str,
+        name: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<TextureType, SceneError> {
+        if let Some(texture) = self.built.get(name) {
+            return Ok(texture.clone());
+        }
+        let desc = self
+            .descs
+            .get(name)
+            .ok_or_else(|| SceneError::invalid(entry, format!("unknown texture `{}`", name)))?;
+        if stack.iter().any(|visiting| visiting == name) {
+            return Err(SceneError::invalid(
+                entry,
+                format!("texture `{}` references itself", name),
+            ));
+        }
+
+        stack.push(String::from(name));
+        let entry = format!("textures.{}", name);
+        let texture: TextureType = match desc {
+            TextureDesc::Solid { color } => {
+                Arc::new(Box::new(SolidColor::new(Color::from_array(*color))))
+            }
+            TextureDesc::Checker { odd, even, scale } => {
+                if *scale <= 0.0 {
+                    return Err(SceneError::invalid(entry, "scale must be positive"));
+                }
+                let odd = self.color(&entry, odd, stack)?;
+                let even = self.color(&entry, even, stack)?;
+                Arc::new(Box::new(Checker::new(odd, even, *scale)))
+            }
+            TextureDesc::Image { path, wrap, filter } => {
+                let wrap = match wrap {
+                    WrapDesc::Repeat => WrapMode::Repeat,
+                    WrapDesc::Clamp => WrapMode::Clamp,
+                    WrapDesc::Mirror => WrapMode::Mirror,
+                };
+                let filter = match filter {
+                    FilterDesc::Nearest => FilterMode::Nearest,
+                    FilterDesc::Bilinear => FilterMode::Bilinear,
+                };
+                let image = ImageTexture::open(self.base_dir.join(path), wrap, filter)
+                    .map_err(|err| SceneError::invalid(&entry, format!("{}: {}", path, err)))?;
+                Arc::new(Box::new(image))
+            }
+            TextureDesc::Noise { kind, scale, color } => {
+                let kind = match kind {
+                    NoiseDesc::Perlin => NoiseKind::Perlin,
+                    NoiseDesc::Turbulence => NoiseKind::Turbulence,
+                    NoiseDesc::Marble => NoiseKind::Marble,
+                };
+                Arc::new(Box::new(NoiseTexture::new(
+                    kind,
+                    *scale,
+                    Color::from_array(*color),
+                )))
+            }
+        };
+        stack.pop();
+
+        self.built.insert(String::from(name), texture.clone());
+        Ok(texture)
+    }
+}
+
+fn build_material(
+    name: &str,
+    desc: &MaterialDesc,
+    textures: &mut TextureLibrary,
+) -> Result<MaterialType, SceneError> {
+    let entry = format!("materials.{}", name);
+    let material: MaterialType = match desc {
+        MaterialDesc::Lambertian { albedo } => Arc::new(Box::new(Lambertian::with_texture(
+            textures.color(&entry, albedo, &mut Vec::new())?,
+        ))),
+        MaterialDesc::Metal { albedo, fuzz } => {
+            if *fuzz < 0.0 {
+                return Err(SceneError::invalid(entry, "fuzz must not be negative"));
+            }
+            Arc::new(Box::new(Metal::with_texture(
+                textures.color(&entry, albedo, &mut Vec::new())?,
+                *fuzz,
+            )))
+        }
+        MaterialDesc::Dielectric { ior } => {
+            if *ior <= 0.0 {
+                return Err(SceneError::invalid(entry, "ior must be positive"));
+            }
+            Arc::new(Box::new(Dielectric::new(*ior)))
+        }
+        MaterialDesc::DiffuseLight { emit, intensity } => match emit {
+            ColorDesc::Color(emit) => {
+                let emit = Color::from_array(*emit) * *intensity;
+                if emit.array().iter().any(|c| *c < 0.0) {
+                    return Err(SceneError::invalid(entry, "emission must not be negative"));
+                }
+                Arc::new(Box::new(DiffuseLight::new(emit)))
+            }
+            ColorDesc::Texture(_) if *intensity != 1.0 => {
+                return Err(SceneError::invalid(
+                    entry,
+                    "intensity can only scale a constant emission color",
+                ))
+            }
+            ColorDesc::Texture(_) => Arc::new(Box::new(DiffuseLight::with_texture(
+                textures.color(&entry, emit, &mut Vec::new())?,
+            ))),
+        },
+        MaterialDesc::Isotropic { albedo } => Arc::new(Box::new(Isotropic::with_texture(
+            textures.color(&entry, albedo, &mut Vec::new())?,
+        ))),
+        MaterialDesc::HenyeyGreenstein { albedo, g } => {
+            if !(-1.0 < *g && *g < 1.0) {
+                return Err(SceneError::invalid(entry, "g must lie in (-1, 1)"));
+            }
+            Arc::new(Box::new(HenyeyGreenstein::with_texture(
+                textures.color(&entry, albedo, &mut Vec::new())?,
+                *g,
+            )))
+        }
+    };
+    Ok(material)
+}
+
+fn build_object(
+    entry: &str,
+    desc: &ObjectDesc,
+    library: &BTreeMap<&str, MaterialType>,
+    shapes: &BTreeMap<&str, ObjectType>,
+    base_dir: &Path,
+) -> Result<ObjectType, SceneError> {
+    let lookup = |name: &str| -> Result<MaterialType, SceneError> {
+        library
+            .get(name)
+            .cloned()
+            .ok_or_else(|| SceneError::invalid(entry, format!("unknown material `{}`", name)))
+    };
+    let check_range = |name: &str, range: [f64; 2]| -> Result<(f64, f64), SceneError> {
+        if range[0] == range[1] {
+            return Err(SceneError::invalid(
+                entry,
+                format!("rectangle `{}` range must not be empty", name),
+            ));
+        }
+        Ok((range[0], range[1]))
+    };
+
+    let object: ObjectType = match desc {
+        ObjectDesc::Sphere {
+            center,
+            center1,
+            radius,
+            material,
+        } => {
+            if *radius == 0.0 {
+                return Err(SceneError::invalid(entry, "sphere radius must not be zero"));
+            }
+            match center1 {
+                Some(center1) => Arc::new(Box::new(MovingSphere::new(
+                    Point3::from_array(*center),
+                    Point3::from_array(*center1),
+                    (0.0, 1.0),
+                    *radius,
+                    lookup(material)?,
+                ))),
+                None => Arc::new(Box::new(Sphere::new(
+                    Point3::from_array(*center),
+                    *radius,
+                    lookup(material)?,
+                ))),
+            }
+        }
+        ObjectDesc::XyRect { x, y, k, material } => Arc::new(Box::new(AxisAlignedRect::xy(
+            check_range("x", *x)?,
+            check_range("y", *y)?,
+            *k,
+            lookup(material)?,
+        ))),
+        ObjectDesc::XzRect { x, z, k, material } => Arc::new(Box::new(AxisAlignedRect::xz(
+            check_range("x", *x)?,
+            check_range("z", *z)?,
+            *k,
+            lookup(material)?,
+        ))),
+        ObjectDesc::YzRect { y, z, k, material } => Arc::new(Box::new(AxisAlignedRect::yz(
+            check_range("y", *y)?,
+            check_range("z", *z)?,
+            *k,
+            lookup(material)?,
+        ))),
+        ObjectDesc::Quad { q, u, v, material } => {
+            let (u, v) = (Vec3::from_array(*u), Vec3::from_array(*v));
+            if Vec3::cross(u, v).near_zero() {
+                return Err(SceneError::invalid(
+                    entry,
+                    "quad edges `u` and `v` must not be parallel",
+                ));
+            }
+            Arc::new(Box::new(Quad::new(
+                Point3::from_array(*q),
+                u,
+                v,
+                lookup(material)?,
+            )))
+        }
+        // 每个网格构建自己的底层 BVH，作为一个物体加入上层
+        ObjectDesc::Mesh {
+            path,
+            material,
+            materials,
+        } => Arc::new(Box::new(BVH::build(build_mesh(
+            entry,
+            path,
+            lookup(material)?,
+            materials,
+            library,
+            base_dir,
+        )?))),
+        ObjectDesc::GridMedium {
+            path,
+            min,
+            max,
+            density,
+            material,
+        } => {
+            if (0..3).any(|axis| min[axis] >= max[axis]) {
+                return Err(SceneError::invalid(entry, "`min` must be below `max`"));
+            }
+            if *density < 0.0 {
+                return Err(SceneError::invalid(entry, "density must not be negative"));
+            }
+            let grid = load_grid(base_dir.join(path))
+                .map_err(|err| SceneError::invalid(entry, format!("{}: {}", path, err)))?;
+            Arc::new(Box::new(GridMedium::new(
+                Arc::new(grid),
+                AABB::new(Vec3::from_array(*min), Vec3::from_array(*max)),
+                *density,
+                lookup(material)?,
+            )))
+        }
+        ObjectDesc::Instance {
+            shape,
+            translate,
+            rotate,
+            scale,
+            motion,
+        } => {
+            let object = shapes
+                .get(shape.as_str())
+                .cloned()
+                .ok_or_else(|| SceneError::invalid(entry, format!("unknown shape `{}`", shape)))?;
+            let start = Keyframe {
+                translate: Vec3::from_array(*translate),
+                rotate: Vec3::from_array(*rotate),
+                scale: scale.factors(),
+            };
+            match motion {
+                Some(motion) => {
+                    let end = Keyframe {
+                        translate: motion.translate.map_or(start.translate, Vec3::from_array),
+                        rotate: motion.rotate.map_or(start.rotate, Vec3::from_array),
+                        scale: motion
+                            .scale
+                            .as_ref()
+                            .map_or(start.scale, ScaleDesc::factors),
+                    };
+                    let transform = MotionTransform::new(object, start, end, (0.0, 1.0))
+                        .ok_or_else(|| {
+                            SceneError::invalid(entry, "scale must not pass through zero")
+                        })?;
+                    Arc::new(Box::new(transform))
+                }
+                None => {
+                    let transform = Transform::new(object, start.matrix())
+                        .ok_or_else(|| SceneError::invalid(entry, "transform is not invertible"))?;
+                    Arc::new(Box::new(transform))
+                }
+            }
+        }
+        ObjectDesc::ConstantMedium {
+            boundary,
+            density,
+            material,
+        } => {
+            if *density <= 0.0 {
+                return Err(SceneError::invalid(entry, "density must be positive"));
+            }
+            let boundary = shapes.get(boundary.as_str()).cloned().ok_or_else(|| {
+                SceneError::invalid(entry, format!("unknown shape `{}`", boundary))
+            })?;
+            Arc::new(Box::new(ConstantMedium::with_phase_function(
+                boundary,
+                *density,
+                lookup(material)?,
+            )))
+        }
+    };
+    Ok(object)
+}
+
+// 将 `shapes` 中的条目构建为单个可共享的物体，网格以自身的 BVH 表示
+fn build_shape(
+    entry: &str,
+    desc: &ObjectDesc,
+    library: &BTreeMap<&str, MaterialType>,
+    base_dir: &Path,
+) -> Result<ObjectType, SceneError> {
+    match desc {
+        ObjectDesc::Instance { .. } => Err(SceneError::invalid(
+            entry,
+            "a shape cannot be an instance of another shape",
+        )),
+        _ => build_object(entry, desc, library, &BTreeMap::new(), base_dir),
+    }
+}
+
+// `material` 为默认材质，`materials` 把 `usemtl` 分组映射到材质库中的名字
+fn build_mesh(
+    entry: &str,
+    path: &str,
+    material: MaterialType,
+    materials: &BTreeMap<String, String>,
+    library: &BTreeMap<&str, MaterialType>,
+    base_dir: &Path,
+) -> Result<Vec<ObjectType>, SceneError> {
+    let lookup = |name: &str| -> Result<MaterialType, SceneError> {
+        library
+            .get(name)
+            .cloned()
+            .ok_or_else(|| SceneError::invalid(entry, format!("unknown material `{}`", name)))
+    };
+
+    let mesh = load_obj(base_dir.join(path))
+        .map_err(|err| SceneError::invalid(entry, format!("{}: {}", path, err)))?;
+    if mesh.triangles().is_empty() {
+        return Err(SceneError::invalid(
+            entry,
+            format!("{}: mesh contains no faces", path),
+        ));
+    }
+
+    let mut group_materials = HashMap::new();
+    for (group, name) in materials {
+        if !mesh.group_names().contains(group) {
+            return Err(SceneError::invalid(
+                entry,
+                format!("{}: no `usemtl {}` group", path, group),
+            ));
+        }
+        group_materials.insert(group.clone(), lookup(name)?);
+    }
+
+    Ok(Arc::new(mesh).objects(material, &group_materials))
+}
+
+fn build_scene(desc: &SceneFile, base_dir: &Path) -> Result<Scene, SceneError> {
+    let mut textures = TextureLibrary::build(&desc.textures, base_dir)?;
+    let mut library = BTreeMap::new();
+    for (name, material) in &desc.materials {
+        library.insert(
+            name.as_str(),
+            build_material(name, material, &mut textures)?,
+        );
+    }
+
+    let mut shapes = BTreeMap::new();
+    for (name, shape) in &desc.shapes {
+        let entry = format!("shapes.{}", name);
+        shapes.insert(
+            name.as_str(),
+            build_shape(&entry, shape, &library, base_dir)?,
+        );
+    }
+
+    let mut scene = Scene::new();
+    for (index, desc) in desc.objects.iter().enumerate() {
+        let entry = format!("objects[{}]", index);
+        scene.add_object(build_object(&entry, desc, &library, &shapes, base_dir)?);
+    }
+
+    if scene.objects.is_empty() {
+        return Err(SceneError::invalid("objects", "scene contains no objects"));
+    }
+
+    scene.build_bvh();
+    Ok(scene)
+}
@@ -1,10 +1,281 @@
-use std::time::Instant;
+use std::{
+    io::{self, IsTerminal, Write},
+    path::Path,
+    process,
+    time::{Duration, Instant},
+};
 
-use rtweekend::Config;
+use clap::Parser;
+use rtweekend::{
+    seed_random, Config, Filter, FilterKind, OutputFormat, Progress, SamplerKind, TileOrder,
+    ToneMapper, BUILTIN_SCENES, MAX_FILTER_RADIUS,
+};
+
+/// Rust实现的光追周末渲染器
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// 内置场景名（cover、three_spheres）或 TOML 场景文件路径
+    #[arg(short, long, default_value = "cover")]
+    scene: String,
+
+    /// 输出图片路径，格式由扩展名决定；.exr、.hdr、.pfm 保存未经截断的线性数据
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// 图片宽度（仅指定宽度时保持宽高比）
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    width: Option<u32>,
+
+    /// 图片高度（仅指定高度时保持宽高比）
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    height: Option<u32>,
+
+    /// 每像素采样数
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    spp: Option<u32>,
+
+    /// 光线最大弹射次数
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    depth: Option<u32>,
+
+    /// 渲染与构建 BVH 的线程数，默认为 CPU 核数
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+
+    /// 随机数种子，决定内置场景的生成与渲染中的随机序列；相同种子的渲染结果逐位相同
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// 渲染区块的边长（像素）
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    tile_size: Option<u32>,
+
+    /// 区块的渲染顺序：scanline、spiral 或 hilbert
+    #[arg(long)]
+    tile_order: Option<TileOrder>,
+
+    /// 按 1、2、4……spp 分遍渲染，定期把中间结果写到输出路径
+    #[arg(long)]
+    progressive: bool,
+
+    /// 渐进渲染时写出中间结果的间隔（秒），指定后自动开启渐进渲染
+    #[arg(long)]
+    snapshot_interval: Option<f64>,
+
+    /// 采样器：independent、stratified、halton、sobol 或 blue_noise
+    #[arg(long)]
+    sampler: Option<SamplerKind>,
+
+    /// 像素重建滤波器：box、tent、gaussian、mitchell 或 lanczos；与场景文件中的种类不同时半径恢复为该滤波器的默认值
+    #[arg(long)]
+    filter: Option<FilterKind>,
+
+    /// 重建滤波器的半径（像素），默认取决于滤波器
+    #[arg(long)]
+    filter_radius: Option<f64>,
+
+    /// 输出 8 位图像时的色调映射：clamp、reinhard、extended_reinhard、aces 或 hable
+    #[arg(long)]
+    tone_map: Option<ToneMapper>,
+
+    /// 曝光补偿（EV），每增加 1 亮度加倍
+    #[arg(long, allow_hyphen_values = true)]
+    exposure: Option<f64>,
+
+    /// 扩展 Reinhard 映射为纯白的辐亮度
+    #[arg(long)]
+    white_point: Option<f64>,
+}
+
+fn load_config(args: &Args) -> Result<Config, String> {
+    if let Some(seed) = args.seed {
+        seed_random(seed);
+    }
+
+    let mut config = match Config::builtin(&args.scene) {
+        Some(config) => config,
+        None if Path::new(&args.scene).is_file() => {
+            Config::from_file(&args.scene).map_err(|err| format!("{}: {}", args.scene, err))?
+        }
+        None => {
+            return Err(format!(
+                "`{}` is neither a built-in scene ({}) nor a scene file",
+                args.scene,
+                BUILTIN_SCENES.join(", ")
+            ))
+        }
+    };
+
+    if let Some(output) = &args.output {
+        config.file_path = output.clone();
+    }
+    if OutputFormat::from_path(&config.file_path).is_none() {
+        return Err(format!(
+            "unsupported output format for `{}`",
+            config.file_path
+        ));
+    }
+
+    let aspect_ratio = config.camera.aspect_ratio();
+    let (width, height) = match (args.width, args.height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (width, ((width as f64 / aspect_ratio) as u32).max(1)),
+        (None, Some(height)) => (((height as f64 * aspect_ratio) as u32).max(1), height),
+        (None, None) => (config.image_width, config.image_height),
+    };
+    if width < 2 || height < 2 {
+        return Err(format!("image size {}x{} is too small", width, height));
+    }
+    config.image_width = width;
+    config.image_height = height;
+    config.camera.set_aspect_ratio(width as f64 / height as f64);
+
+    if let Some(spp) = args.spp {
+        config.samples_per_pixel = spp;
+    }
+    if let Some(depth) = args.depth {
+        config.camera.set_ray_depth(depth);
+    }
+    if let Some(threads) = args.threads {
+        config.threads = threads as usize;
+    }
+    if let Some(seed) = args.seed {
+        config.seed = seed;
+    }
+    if let Some(tile_size) = args.tile_size {
+        config.tile_size = tile_size;
+    }
+    if let Some(tile_order) = args.tile_order {
+        config.tile_order = tile_order;
+    }
+    if let Some(sampler) = args.sampler {
+        config.sampler = sampler;
+    }
+    if let Some(kind) = args.filter {
+        if kind != config.filter.kind {
+            config.filter = Filter::new(kind);
+        }
+    }
+    if let Some(radius) = args.filter_radius {
+        if radius.is_nan() || radius <= 0.0 || radius > MAX_FILTER_RADIUS {
+            return Err(format!(
+                "filter radius must be in (0, {}]",
+                MAX_FILTER_RADIUS
+            ));
+        }
+        config.filter.radius = radius;
+    }
+    if let Some(operator) = args.tone_map {
+        config.tone_mapping.operator = operator;
+    }
+    if let Some(exposure) = args.exposure {
+        if !exposure.is_finite() {
+            return Err(format!("invalid exposure {}", exposure));
+        }
+        config.tone_mapping.exposure = exposure;
+    }
+    if let Some(white_point) = args.white_point {
+        if white_point.is_nan() || white_point <= 0.0 {
+            return Err(format!("invalid white point {}", white_point));
+        }
+        config.tone_mapping.white_point = white_point;
+    }
+    config.progressive = args.progressive || args.snapshot_interval.is_some();
+    if let Some(interval) = args.snapshot_interval {
+        config.snapshot_interval = Duration::try_from_secs_f64(interval)
+            .map_err(|_| format!("invalid snapshot interval {}", interval))?;
+    }
+
+    Ok(config)
+}
+
+/// 输出到 stderr 的进度条，stderr 不是终端时不显示
+struct ProgressBar {
+    enabled: bool,
+    last_draw: Option<Instant>,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 30;
+    const REFRESH: Duration = Duration::from_millis(100);
+
+    fn new() -> ProgressBar {
+        ProgressBar {
+            enabled: io::stderr().is_terminal(),
+            last_draw: None,
+        }
+    }
+
+    fn update(&mut self, progress: &Progress) {
+        let due = self
+            .last_draw
+            .is_none_or(|last| last.elapsed() >= Self::REFRESH);
+        if !self.enabled || !(due || progress.is_finished()) {
+            return;
+        }
+        self.last_draw = Some(Instant::now());
+
+        let filled = (progress.fraction() * Self::WIDTH as f64) as usize;
+        let eta = progress
+            .eta()
+            .map_or(String::from("--:--"), format_duration);
+        let passes = match progress.total_passes {
+            1 => String::new(),
+            total => format!("pass {}/{}  ", progress.completed_passes, total),
+        };
+        let mut stderr = io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r[{}{}] {:5.1}%  {}/{} tiles  {}elapsed {}  ETA {}  {:.2} Mrays/s ",
+            "=".repeat(filled),
+            " ".repeat(Self::WIDTH - filled),
+            progress.fraction() * 100.0,
+            progress.completed_tiles,
+            progress.total_tiles,
+            passes,
+            format_duration(progress.elapsed),
+            eta,
+            progress.rays_per_second() / 1e6
+        );
+        if progress.is_finished() {
+            let _ = writeln!(stderr);
+        }
+        let _ = stderr.flush();
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds / 3600 {
+        0 => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
 
 fn main() {
+    let args = Args::parse();
+    // 加载场景时并行构建 BVH，在限定线程数的 rayon 线程池中进行（0 表示按 CPU 核数）
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads.unwrap_or_default() as usize)
+        .build()
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+    let config = pool.install(|| load_config(&args)).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(2);
+    });
+    println!("{}", config.scene.bvh.stats());
+
     let now = Instant::now();
-    let config = Config::new();
-    rtweekend::run(config).unwrap();
+    let mut progress_bar = ProgressBar::new();
+    if let Err(err) =
+        rtweekend::run_with_progress(config, move |progress| progress_bar.update(progress))
+    {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
     println!("Total time: {}", now.elapsed().as_secs_f64());
 }
@@ -12,7 +12,12 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray_in: Ray, hit_record: &HitRecord) -> Option<(Ray, Color)> {
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
         let attenuation = Color::new_color(1.0, 1.0, 1.0);
         let refraction_ratio = match hit_record.front_face {
             true => 1.0 / self.ior,
@@ -23,7 +28,12 @@ impl Material for Dielectric {
 
         let scattered = Ray::new(
             hit_record.hit_point,
-            match is_reflect(unit_direction, hit_record.hit_normal, refraction_ratio) {
+            match is_reflect(
+                unit_direction,
+                hit_record.hit_normal,
+                refraction_ratio,
+                sampler.get_1d(),
+            ) {
                 true => Vec3::reflect(unit_direction, hit_record.hit_normal),
                 false => Vec3::refract(
                     ray_in.dir.unit_vector(),
@@ -32,13 +42,15 @@ impl Material for Dielectric {
                 ),
             },
             ray_in.depth - 1,
-        );
+        )
+        .with_time(ray_in.time);
 
         Option::Some((scattered, attenuation))
     }
 }
 
-fn is_reflect(ray_in: Vec3, normal: Vec3, ior: f64) -> bool {
+// 全反射，或以 Schlick 近似的反射率按 `u` 随机选择反射
+fn is_reflect(ray_in: Vec3, normal: Vec3, ior: f64, u: f64) -> bool {
     let cos_theta = Vec3::dot(ray_in * -1.0, normal).min(1.0);
 
     let sin_theta = (1.0 - cos_theta.powf(2.0)).sqrt();
@@ -46,5 +58,5 @@ fn is_reflect(ray_in: Vec3, normal: Vec3, ior: f64) -> bool {
     let reflectance = ((1.0 - ior) / (1.0 + ior)).powf(2.0);
     let reflectance = reflectance + (1.0 - reflectance) * (1.0 - cos_theta).powf(5.0);
 
-    ior * sin_theta > 1.0 || reflectance > random_01()
+    ior * sin_theta > 1.0 || reflectance > u
 }
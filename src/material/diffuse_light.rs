@@ -0,0 +1,37 @@
+use crate::*;
+
+pub struct DiffuseLight {
+    emit: TextureType,
+}
+
+impl Default for DiffuseLight {
+    fn default() -> Self {
+        DiffuseLight::new(Color::default())
+    }
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> DiffuseLight {
+        DiffuseLight::with_texture(Arc::new(Box::new(SolidColor::new(emit))))
+    }
+
+    pub fn with_texture(emit: TextureType) -> DiffuseLight {
+        DiffuseLight { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _ray_in: Ray,
+        _hit_record: &HitRecord,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
+        Option::None
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Color {
+        self.emit
+            .value(hit_record.u, hit_record.v, hit_record.hit_point)
+    }
+}
@@ -0,0 +1,71 @@
+use std::f64::consts::PI;
+
+use crate::*;
+
+/// Henyey–Greenstein 相函数，`g` 为平均散射余弦：正值前向散射，负值后向散射，零为各向同性
+pub struct HenyeyGreenstein {
+    albedo: TextureType,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Color, g: f64) -> HenyeyGreenstein {
+        HenyeyGreenstein::with_texture(Arc::new(Box::new(SolidColor::new(albedo))), g)
+    }
+
+    pub fn with_texture(albedo: TextureType, g: f64) -> HenyeyGreenstein {
+        HenyeyGreenstein {
+            albedo,
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+
+    pub fn g(&self) -> f64 {
+        self.g
+    }
+
+    /// 入射传播方向与出射方向夹角余弦为 `cos_theta` 时的相函数值
+    pub fn phase(&self, cos_theta: f64) -> f64 {
+        let g = self.g;
+        let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+    }
+
+    // 按相函数重要性采样散射角的余弦
+    fn sample_cos_theta(&self, xi: f64) -> f64 {
+        let g = self.g;
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * xi;
+        }
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
+        let forward = ray_in.dir.unit_vector();
+        let (tangent, bitangent) = Vec3::orthonormal_basis(forward);
+
+        let u = sampler.get_2d();
+        let cos_theta = self.sample_cos_theta(u.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u.1;
+        let dir = tangent * (sin_theta * phi.cos())
+            + bitangent * (sin_theta * phi.sin())
+            + forward * cos_theta;
+
+        let scattered =
+            Ray::new(hit_record.hit_point, dir, ray_in.depth - 1).with_time(ray_in.time);
+        let attenuation = self
+            .albedo
+            .value(hit_record.u, hit_record.v, hit_record.hit_point);
+
+        Option::Some((scattered, attenuation))
+    }
+}
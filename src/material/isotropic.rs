@@ -0,0 +1,44 @@
+use crate::*;
+
+/// 各向同性相函数，参与介质内的散射方向在单位球面上均匀分布
+pub struct Isotropic {
+    albedo: TextureType,
+}
+
+impl Default for Isotropic {
+    fn default() -> Self {
+        Isotropic::new(Color::new_color(1.0, 1.0, 1.0))
+    }
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Isotropic {
+        Isotropic::with_texture(Arc::new(Box::new(SolidColor::new(albedo))))
+    }
+
+    pub fn with_texture(albedo: TextureType) -> Isotropic {
+        Isotropic { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
+        let scattered = Ray::new(
+            hit_record.hit_point,
+            sample_unit_sphere(sampler.get_2d()),
+            ray_in.depth - 1,
+        )
+        .with_time(ray_in.time);
+
+        let attenuation = self
+            .albedo
+            .value(hit_record.u, hit_record.v, hit_record.hit_point);
+
+        Option::Some((scattered, attenuation))
+    }
+}
@@ -1,28 +1,47 @@
 use crate::*;
 
-#[derive(Default)]
 pub struct Lambertian {
-    albedo: Color,
+    albedo: TextureType,
+}
+
+impl Default for Lambertian {
+    fn default() -> Self {
+        Lambertian::new(Color::default())
+    }
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Lambertian {
+        Lambertian::with_texture(Arc::new(Box::new(SolidColor::new(albedo))))
+    }
+
+    pub fn with_texture(albedo: TextureType) -> Lambertian {
         Lambertian { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray_in: Ray, hit_record: &HitRecord) -> Option<(Ray, Color)> {
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
         let mut scattered = Ray::new(
             hit_record.hit_point,
-            random_hemisphere(hit_record.hit_normal),
+            sample_hemisphere(hit_record.hit_normal, sampler.get_2d()),
             ray_in.depth - 1,
-        );
+        )
+        .with_time(ray_in.time);
 
         if scattered.dir.near_zero() {
             scattered.dir = hit_record.hit_normal;
         }
 
-        Option::Some((scattered, self.albedo))
+        let attenuation = self
+            .albedo
+            .value(hit_record.u, hit_record.v, hit_record.hit_point);
+
+        Option::Some((scattered, attenuation))
     }
 }
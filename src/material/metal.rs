@@ -1,13 +1,22 @@
 use crate::*;
 
-#[derive(Default)]
 pub struct Metal {
-    albedo: Color,
+    albedo: TextureType,
     fuzz: f64,
 }
 
+impl Default for Metal {
+    fn default() -> Self {
+        Metal::new(Color::default(), 0.0)
+    }
+}
+
 impl Metal {
     pub fn new(albedo: Color, fuzz: f64) -> Metal {
+        Metal::with_texture(Arc::new(Box::new(SolidColor::new(albedo))), fuzz)
+    }
+
+    pub fn with_texture(albedo: TextureType, fuzz: f64) -> Metal {
         Metal {
             albedo,
             fuzz: {
@@ -22,16 +31,26 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray_in: Ray, hit_record: &HitRecord) -> Option<(Ray, Color)> {
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)> {
         let scattered = Ray::new(
             hit_record.hit_point,
             Vec3::reflect(ray_in.dir.unit_vector(), hit_record.hit_normal)
-                + random_unit_sphere() * self.fuzz,
+                + sample_unit_ball(sampler.get_2d(), sampler.get_1d()) * self.fuzz,
             ray_in.depth - 1,
-        );
+        )
+        .with_time(ray_in.time);
+
+        let attenuation = self
+            .albedo
+            .value(hit_record.u, hit_record.v, hit_record.hit_point);
 
         match Vec3::dot(scattered.dir, hit_record.hit_normal) > 0.0 {
-            true => Option::Some((scattered, self.albedo)),
+            true => Option::Some((scattered, attenuation)),
             false => Option::None,
         }
     }
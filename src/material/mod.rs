@@ -1,9 +1,23 @@
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
 
 use crate::*;
 
 pub trait Material {
-    fn scatter(&self, ray_in: Ray, hit_record: &HitRecord) -> Option<(Ray, Color)>;
+    /// 散射方向所需的随机数从 `sampler` 中按顺序取用
+    fn scatter(
+        &self,
+        ray_in: Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> Option<(Ray, Color)>;
+
+    /// 材质在击中点处的自发光辐亮度，默认不发光
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        Color::new_color(0.0, 0.0, 0.0)
+    }
 }
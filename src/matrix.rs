@@ -0,0 +1,185 @@
+use std::{fmt::Display, ops::Mul};
+
+use crate::*;
+
+/// 行主序的 4x4 矩阵，用于仿射变换
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4(pub [[f64; 4]; 4]);
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::identity()
+    }
+}
+
+impl Mat4 {
+    pub fn identity() -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Mat4(m)
+    }
+
+    pub fn translate(offset: Vec3) -> Mat4 {
+        let mut m = Mat4::identity();
+        m.0[0][3] = offset.x();
+        m.0[1][3] = offset.y();
+        m.0[2][3] = offset.z();
+        m
+    }
+
+    pub fn scale(factor: Vec3) -> Mat4 {
+        let mut m = Mat4::identity();
+        m.0[0][0] = factor.x();
+        m.0[1][1] = factor.y();
+        m.0[2][2] = factor.z();
+        m
+    }
+
+    /// 绕任意轴旋转 `degrees` 度（右手定则）
+    pub fn rotate(axis: Vec3, degrees: f64) -> Mat4 {
+        let Vec3(x, y, z) = axis.unit_vector();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let k = 1.0 - cos;
+        Mat4([
+            [
+                cos + x * x * k,
+                x * y * k - z * sin,
+                x * z * k + y * sin,
+                0.0,
+            ],
+            [
+                y * x * k + z * sin,
+                cos + y * y * k,
+                y * z * k - x * sin,
+                0.0,
+            ],
+            [
+                z * x * k - y * sin,
+                z * y * k + x * sin,
+                cos + z * z * k,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotate_x(degrees: f64) -> Mat4 {
+        Mat4::rotate(Vec3(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(degrees: f64) -> Mat4 {
+        Mat4::rotate(Vec3(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(degrees: f64) -> Mat4 {
+        Mat4::rotate(Vec3(0.0, 0.0, 1.0), degrees)
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.0[j][i];
+            }
+        }
+        Mat4(m)
+    }
+
+    /// 高斯-约当消元求逆，矩阵奇异时返回 `None`
+    pub fn inverse(&self) -> Option<Mat4> {
+        let mut a = self.0;
+        let mut inv = Mat4::identity().0;
+
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+                .unwrap_or(col);
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+
+        Some(Mat4(inv))
+    }
+
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        let m = &self.0;
+        let x = m[0][0] * p.0 + m[0][1] * p.1 + m[0][2] * p.2 + m[0][3];
+        let y = m[1][0] * p.0 + m[1][1] * p.1 + m[1][2] * p.2 + m[1][3];
+        let z = m[2][0] * p.0 + m[2][1] * p.1 + m[2][2] * p.2 + m[2][3];
+        let w = m[3][0] * p.0 + m[3][1] * p.1 + m[3][2] * p.2 + m[3][3];
+        match w == 1.0 {
+            true => Vec3(x, y, z),
+            false => Vec3(x, y, z) / w,
+        }
+    }
+
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.0;
+        Vec3(
+            m[0][0] * v.0 + m[0][1] * v.1 + m[0][2] * v.2,
+            m[1][0] * v.0 + m[1][1] * v.1 + m[1][2] * v.2,
+            m[2][0] * v.0 + m[2][1] * v.1 + m[2][2] * v.2,
+        )
+    }
+
+    pub fn transform_aabb(&self, aabb: AABB) -> AABB {
+        let (min, max) = (aabb.min(), aabb.max());
+        let corner = |i: usize| {
+            Vec3(
+                if i & 1 == 0 { min.x() } else { max.x() },
+                if i & 2 == 0 { min.y() } else { max.y() },
+                if i & 4 == 0 { min.z() } else { max.z() },
+            )
+        };
+
+        let first = self.transform_point(corner(0));
+        (1..8).fold(AABB::new(first, first), |result, i| {
+            let p = self.transform_point(corner(i));
+            result + AABB::new(p, p)
+        })
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.0[i][k] * rhs.0[k][j]).sum();
+            }
+        }
+        Mat4(m)
+    }
+}
+
+impl Display for Mat4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.0.iter() {
+            writeln!(f, "[{}, {}, {}, {}]", row[0], row[1], row[2], row[3])?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::{error::Error, fmt::Display, fs, path::Path};
+
+use crate::*;
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(String, std::io::Error),
+    Parse(usize, String),
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::Io(path, err) => write!(f, "could not read OBJ file `{}`: {}", path, err),
+            ObjError::Parse(line, message) => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ObjError {}
+
+/// 读取 Wavefront OBJ 文件，多边形面以扇形三角化，`usemtl` 作为材质分组
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<TriangleMesh, ObjError> {
+    let path = path.as_ref().display().to_string();
+    let text = fs::read_to_string(&path).map_err(|err| ObjError::Io(path.clone(), err))?;
+    parse_obj(&text)
+}
+
+pub fn parse_obj(text: &str) -> Result<TriangleMesh, ObjError> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut triangles = Vec::new();
+    let mut groups = Vec::new();
+    let mut group_names: Vec<String> = Vec::new();
+    let mut current_group = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => positions.push(Vec3::from_array(parse_floats::<3>(number, &arguments)?)),
+            "vn" => normals.push(Vec3::from_array(parse_floats::<3>(number, &arguments)?)),
+            "vt" => {
+                let [u, v] = parse_floats::<2>(number, &arguments)?;
+                uvs.push((u, v));
+            }
+            "f" => {
+                if arguments.len() < 3 {
+                    return Err(ObjError::Parse(
+                        number,
+                        String::from("face needs at least 3 vertices"),
+                    ));
+                }
+                let counts = (positions.len(), uvs.len(), normals.len());
+                let vertices = arguments
+                    .iter()
+                    .map(|argument| parse_vertex(number, argument, counts))
+                    .collect::<Result<Vec<_>, _>>()?;
+                for i in 1..vertices.len() - 1 {
+                    triangles.push([vertices[0], vertices[i], vertices[i + 1]]);
+                    groups.push(current_group);
+                }
+            }
+            "usemtl" => {
+                let name = arguments.join(" ");
+                current_group = Some(match group_names.iter().position(|group| *group == name) {
+                    Some(index) => index,
+                    None => {
+                        group_names.push(name);
+                        group_names.len() - 1
+                    }
+                });
+            }
+            // 对象、分组、平滑组、材质库以及线、点和自由曲线曲面等语句不影响三角网格，直接忽略
+            _ => {}
+        }
+    }
+
+    Ok(TriangleMesh::new(positions, normals, uvs, triangles).with_groups(groups, group_names))
+}
+
+// 解析前 N 个浮点数，忽略多余的分量（如 `v` 的 w 分量）
+fn parse_floats<const N: usize>(line: usize, arguments: &[&str]) -> Result<[f64; N], ObjError> {
+    if arguments.len() < N {
+        return Err(ObjError::Parse(
+            line,
+            format!("expected {} numbers, found {}", N, arguments.len()),
+        ));
+    }
+    let mut result = [0.0; N];
+    for (value, argument) in result.iter_mut().zip(arguments) {
+        *value = argument
+            .parse()
+            .map_err(|_| ObjError::Parse(line, format!("invalid number `{}`", argument)))?;
+    }
+    Ok(result)
+}
+
+// 解析 `v`、`v/vt`、`v//vn`、`v/vt/vn` 形式的面顶点，支持负数（相对）索引
+fn parse_vertex(
+    line: usize,
+    argument: &str,
+    (position_count, uv_count, normal_count): (usize, usize, usize),
+) -> Result<VertexIndex, ObjError> {
+    let resolve = |token: &str, count: usize, kind: &str| -> Result<usize, ObjError> {
+        let index: i64 = token
+            .parse()
+            .map_err(|_| ObjError::Parse(line, format!("invalid {} index `{}`", kind, token)))?;
+        let resolved = match index {
+            index if index > 0 => index - 1,
+            index if index < 0 => count as i64 + index,
+            _ => -1,
+        };
+        if resolved < 0 || resolved >= count as i64 {
+            return Err(ObjError::Parse(
+                line,
+                format!("{} index {} out of range", kind, index),
+            ));
+        }
+        Ok(resolved as usize)
+    };
+
+    let mut parts = argument.split('/');
+    let position = resolve(parts.next().unwrap_or_default(), position_count, "vertex")?;
+    let uv = match parts.next() {
+        Some("") | None => None,
+        Some(token) => Some(resolve(token, uv_count, "texture coordinate")?),
+    };
+    let normal = match parts.next() {
+        Some("") | None => None,
+        Some(token) => Some(resolve(token, normal_count, "normal")?),
+    };
+
+    Ok(VertexIndex {
+        position,
+        normal,
+        uv,
+    })
+}
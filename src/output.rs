@@ -0,0 +1,88 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::*;
+
+use image::{codecs::hdr::HdrEncoder, ImageError, ImageFormat, Rgb32FImage, RgbImage};
+
+/// 输出图片的格式，由文件扩展名决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    OpenExr,
+    /// Radiance RGBE
+    Hdr,
+    /// Portable Float Map
+    Pfm,
+    /// 色调映射后量化为 8 位，由 `image` 编码（PNG、JPEG 等）
+    Ldr(ImageFormat),
+}
+
+impl OutputFormat {
+    /// 按扩展名识别格式，无法写出的格式返回 `None`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<OutputFormat> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "exr" => Some(OutputFormat::OpenExr),
+            "hdr" => Some(OutputFormat::Hdr),
+            "pfm" => Some(OutputFormat::Pfm),
+            _ => ImageFormat::from_extension(extension)
+                .filter(|format| format.can_write() && *format != ImageFormat::OpenExr)
+                .map(OutputFormat::Ldr),
+        }
+    }
+
+    /// 是否保留线性的高动态范围数据
+    pub fn is_hdr(&self) -> bool {
+        !matches!(self, OutputFormat::Ldr(_))
+    }
+}
+
+/// 把线性颜色的图像按 `path` 的扩展名写出，高动态范围格式原样保存，其余格式先经 `tone_mapping`
+pub fn save_image<P: AsRef<Path>>(
+    image: &Rgb32FImage,
+    path: P,
+    tone_mapping: &ToneMapping,
+) -> Result<(), ImageError> {
+    let path = path.as_ref();
+    match OutputFormat::from_path(path) {
+        Some(OutputFormat::OpenExr) => image.save_with_format(path, ImageFormat::OpenExr),
+        Some(OutputFormat::Hdr) => {
+            let writer = BufWriter::new(File::create(path)?);
+            let pixels: Vec<_> = image.pixels().copied().collect();
+            HdrEncoder::new(writer).encode(&pixels, image.width() as usize, image.height() as usize)
+        }
+        Some(OutputFormat::Pfm) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            writer.write_all(&encode_pfm(image))?;
+            Ok(writer.flush()?)
+        }
+        // 不支持的格式交给 `image` 报错
+        _ => quantize(image, tone_mapping).save(path),
+    }
+}
+
+/// 线性颜色经色调映射与 sRGB 编码后量化为 8 位
+pub fn quantize(image: &Rgb32FImage, tone_mapping: &ToneMapping) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y);
+        tone_mapping.to_rgb8(Color::new_color(
+            pixel[0] as f64,
+            pixel[1] as f64,
+            pixel[2] as f64,
+        ))
+    })
+}
+
+/// 编码为彩色 PFM：小端序（比例因子为负），扫描行自下而上
+pub fn encode_pfm(image: &Rgb32FImage) -> Vec<u8> {
+    let mut bytes = format!("PF\n{} {}\n-1.0\n", image.width(), image.height()).into_bytes();
+    for row in image.rows().rev() {
+        for channel in row.flat_map(|pixel| pixel.0) {
+            bytes.extend_from_slice(&channel.to_le_bytes());
+        }
+    }
+    bytes
+}
@@ -1,15 +1,39 @@
+use std::cell::Cell;
+
 use crate::*;
 
+thread_local! {
+    // 当前线程追踪过的光线数，用于统计渲染速度
+    static RAY_COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+/// 取出并清零当前线程追踪过的光线数
+pub fn take_ray_count() -> u64 {
+    RAY_COUNT.with(|count| count.replace(0))
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Ray {
     pub orig: Point3,
     pub dir: Vec3,
     pub depth: u32,
+    pub time: f64,
 }
 
 impl Ray {
     pub fn new(orig: Point3, dir: Vec3, depth: u32) -> Ray {
-        Ray { orig, dir, depth }
+        Ray {
+            orig,
+            dir,
+            depth,
+            time: 0.0,
+        }
+    }
+
+    /// 设置光线发出的时刻，用于运动模糊
+    pub fn with_time(mut self, time: f64) -> Ray {
+        self.time = time;
+        self
     }
 
     pub fn at(&self, t: f64) -> Point3 {
@@ -17,18 +41,22 @@ impl Ray {
     }
 }
 
-pub fn ray_color(ray: Ray, config: &Config) -> Color {
+/// 沿光线递归估计辐亮度，散射所需的随机数取自 `sampler`
+pub fn ray_color(ray: Ray, config: &Config, sampler: &mut dyn Sampler) -> Color {
     if ray.depth == 0 {
         return Color::new_color(0.0, 0.0, 0.0);
     }
 
+    RAY_COUNT.with(|count| count.set(count.get() + 1));
     if let Some(hit_record) = config.scene.hit(&ray, (1e-8, f64::INFINITY)) {
-        if let Some((scattered, attenuation)) = hit_record.hit_material.scatter(ray, &hit_record) {
-            return attenuation * ray_color(scattered, config);
+        let emitted = hit_record.hit_material.emitted(&hit_record);
+        if let Some((scattered, attenuation)) =
+            hit_record.hit_material.scatter(ray, &hit_record, sampler)
+        {
+            return emitted + attenuation * ray_color(scattered, config, sampler);
         }
-        Color::new_color(0.0, 0.0, 0.0)
+        emitted
     } else {
-        let t = 0.5 * (ray.dir.unit_vector().y() + 1.0);
-        Color::new_color(1.0, 1.0, 1.0) * (1.0 - t) + Color::new_color(0.5, 0.7, 1.0) * t
+        config.scene.background.radiance(ray.dir)
     }
 }
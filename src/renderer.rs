@@ -1,16 +1,77 @@
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    sync::mpsc::{channel, Receiver, Sender},
+    time::{Duration, Instant},
+};
 
 use crate::*;
 
-use image::{Rgb, RgbImage};
+use image::{Rgb32FImage, RgbImage};
 use threadpool::ThreadPool;
 
+/// 渲染进度，每完成一个区块报告一次
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// 所有遍数中已完成的区块数
+    pub completed_tiles: usize,
+    pub total_tiles: usize,
+    pub completed_passes: usize,
+    pub total_passes: usize,
+    /// 已完成的像素采样数（像素数 x 每像素采样数）
+    pub completed_samples: u64,
+    pub total_samples: u64,
+    /// 已追踪的光线数，包括弹射产生的光线
+    pub rays: u64,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f64 {
+        match self.total_samples {
+            0 => 1.0,
+            total => self.completed_samples as f64 / total as f64,
+        }
+    }
+
+    /// 按目前的平均速度估计的剩余时间，尚无完成的采样时返回 `None`
+    pub fn eta(&self) -> Option<Duration> {
+        if self.completed_samples == 0 {
+            return None;
+        }
+        let remaining = (self.total_samples - self.completed_samples) as f64;
+        Some(
+            self.elapsed
+                .mul_f64(remaining / self.completed_samples as f64),
+        )
+    }
+
+    pub fn rays_per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            seconds if seconds > 0.0 => self.rays as f64 / seconds,
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.completed_tiles == self.total_tiles
+    }
+}
+
+pub type ProgressCallback = Box<dyn FnMut(&Progress)>;
+
+// 渲染完的区块在本遍中的序号、区块、其采样溅射到的胶片与追踪的光线数
+type TileResult = (usize, Tile, Film, u64);
+
 pub struct Renderer {
     config: ConfigType,
-    image: RgbImage,
+    film: Film,
+    samples: u32,
     pool: ThreadPool,
-    transmitter: Sender<(u32, u32, Rgb<u8>)>,
-    receiver: Receiver<(u32, u32, Rgb<u8>)>,
+    transmitter: Sender<TileResult>,
+    receiver: Receiver<TileResult>,
+    progress: Option<ProgressCallback>,
 }
 
 impl Renderer {
@@ -18,45 +79,217 @@ impl Renderer {
         let (transmitter, receiver) = channel();
         Renderer {
             config: config.clone(),
-            image: RgbImage::new(config.image_width as u32, config.image_height as u32),
-            pool: ThreadPool::new(num_cpus::get()),
+            film: Film::new(config.image_width, config.image_height, config.filter),
+            samples: 0,
+            pool: ThreadPool::new(config.threads),
             transmitter,
             receiver,
+            progress: None,
         }
     }
 
+    /// 设置进度回调，在主线程上每完成一个区块调用一次
+    pub fn set_progress<F: FnMut(&Progress) + 'static>(&mut self, callback: F) {
+        self.progress = Some(Box::new(callback));
+    }
+
     pub fn threadpool(&self) -> &ThreadPool {
         &self.pool
     }
 
-    pub fn sender(&self) -> Sender<(u32, u32, Rgb<u8>)> {
-        self.transmitter.clone()
+    /// 每个像素已累积的采样数
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub fn film(&self) -> &Film {
+        &self.film
+    }
+
+    /// 像素 (x, y) 目前的重建结果（线性）
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        self.film.pixel(x, y)
+    }
+
+    /// 当前的重建结果（线性），未经色调映射与截断
+    pub fn linear_image(&self) -> Rgb32FImage {
+        self.film.to_image()
     }
 
-    pub fn save_png(&mut self) {
-        for _ in 0..(self.image.width() * self.image.height()) {
-            let (x, y, pixel) = self.receiver.recv().unwrap();
-            self.image.put_pixel(x, y, pixel);
+    /// 当前累积结果经色调映射后的 8 位图像
+    pub fn image(&self) -> RgbImage {
+        quantize(&self.linear_image(), &self.config.tone_mapping)
+    }
+
+    /// 按 `sample_passes` 分遍渲染并累积到浮点缓冲区；渐进模式下每隔 `snapshot_interval`
+    /// 把中间结果写到输出路径，第一遍结束后总会写一次
+    pub fn render(&mut self) -> Result<(), image::ImageError> {
+        let tiles = generate_tiles(
+            self.config.image_width,
+            self.config.image_height,
+            self.config.tile_size,
+            self.config.tile_order,
+        );
+        let passes = match self.config.progressive {
+            true => sample_passes(self.config.samples_per_pixel),
+            false => vec![self.config.samples_per_pixel],
+        };
+
+        let mut progress = Progress {
+            completed_tiles: 0,
+            total_tiles: tiles.len() * passes.len(),
+            completed_passes: 0,
+            total_passes: passes.len(),
+            completed_samples: 0,
+            total_samples: self.config.image_width as u64
+                * self.config.image_height as u64
+                * self.config.samples_per_pixel as u64,
+            rays: 0,
+            elapsed: Duration::ZERO,
+        };
+        let start = Instant::now();
+        let mut last_snapshot: Option<Instant> = None;
+        for (pass, samples) in passes.iter().copied().enumerate() {
+            self.render_pass(&tiles, samples, &mut progress, start);
+
+            let is_last = pass + 1 == passes.len();
+            let due =
+                last_snapshot.is_none_or(|last| last.elapsed() >= self.config.snapshot_interval);
+            if !is_last && due {
+                self.save_snapshot()?;
+                last_snapshot = Some(Instant::now());
+            }
         }
+        Ok(())
+    }
+
+    // 所有区块各追加 `samples` 个采样
+    fn render_pass(
+        &mut self,
+        tiles: &[Tile],
+        samples: u32,
+        progress: &mut Progress,
+        start: Instant,
+    ) {
+        let first_sample = self.samples;
+        for (index, tile) in tiles.iter().copied().enumerate() {
+            let config = self.config.clone();
+            let sender = self.transmitter.clone();
+            self.pool.execute(move || {
+                take_ray_count();
+                let mut film =
+                    Film::for_tile(tile, config.image_width, config.image_height, config.filter);
+                let mut sampler = config.create_sampler();
+                for (x, y) in tile.pixels() {
+                    render_samples(
+                        &config,
+                        sampler.as_mut(),
+                        x,
+                        y,
+                        first_sample,
+                        samples,
+                        |position, color| film.add_sample(position, color),
+                    );
+                }
+                sender
+                    .send((index, tile, film, take_ray_count()))
+                    .expect("Could not send tile");
+            });
+        }
+
+        // 重建滤波器使相邻区块的胶片互有重叠，按区块顺序合并才能保证浮点累加的结果与完成顺序无关
+        let mut pending = BTreeMap::new();
+        let mut next_merge = 0;
+        for received in 1..=tiles.len() {
+            let (index, tile, film, rays) = self.receiver.recv().unwrap();
+            pending.insert(index, film);
+            while let Some(film) = pending.remove(&next_merge) {
+                self.film.merge(&film);
+                next_merge += 1;
+            }
 
-        self.image.save(self.config.file_path.clone()).unwrap();
+            progress.completed_tiles += 1;
+            progress.completed_samples += tile.pixel_count() as u64 * samples as u64;
+            progress.rays += rays;
+            progress.elapsed = start.elapsed();
+            if received == tiles.len() {
+                progress.completed_passes += 1;
+            }
+            if let Some(callback) = self.progress.as_mut() {
+                callback(progress);
+            }
+        }
+        self.samples += samples;
+    }
+
+    /// 按 `file_path` 的扩展名保存：`.exr`、`.hdr`、`.pfm` 保存线性数据，其余格式保存 8 位图像
+    pub fn save(&self) -> Result<(), image::ImageError> {
+        save_image(
+            &self.linear_image(),
+            &self.config.file_path,
+            &self.config.tone_mapping,
+        )
+    }
+
+    // 先写到同目录下的临时文件再改名，中途中断时输出路径上总是一张完整的图片
+    fn save_snapshot(&self) -> Result<(), image::ImageError> {
+        let path = Path::new(&self.config.file_path);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temporary = path.with_file_name(format!(".{}", name));
+        save_image(&self.linear_image(), &temporary, &self.config.tone_mapping)?;
+        fs::rename(&temporary, path).map_err(image::ImageError::IoError)
     }
 }
 
-pub fn gamma_correct(pixel_color: Color) -> Rgb<u8> {
-    let color = Color::new_color(
-        f64::sqrt(pixel_color.0),
-        f64::sqrt(pixel_color.1),
-        f64::sqrt(pixel_color.2),
-    );
+/// 渐进渲染各遍的采样数：累计采样数依次为 1、2、4、8……，最后一遍补足到 `samples_per_pixel`
+pub fn sample_passes(samples_per_pixel: u32) -> Vec<u32> {
+    let mut passes = Vec::new();
+    let mut total = 0;
+    while total < samples_per_pixel {
+        let samples = total.max(1).min(samples_per_pixel - total);
+        passes.push(samples);
+        total += samples;
+    }
+    passes
+}
 
-    Rgb(convert_color_to_u8(color))
+/// 像素 (i, j) 内采样的平均颜色（不经重建滤波器），图像坐标以左上角为原点
+pub fn render_pixel(config: &Config, i: u32, j: u32) -> Color {
+    let mut sampler = config.create_sampler();
+    let mut pixel_color = Color::new_color(0.0, 0.0, 0.0);
+    let samples = config.samples_per_pixel;
+    render_samples(config, sampler.as_mut(), i, j, 0, samples, |_, color| {
+        pixel_color += color
+    });
+    pixel_color / config.samples_per_pixel as f64
 }
 
-fn convert_color_to_u8(pixel_color: Color) -> [u8; 3] {
-    [
-        (256.0 * pixel_color.0) as u8,
-        (256.0 * pixel_color.1) as u8,
-        (256.0 * pixel_color.2) as u8,
-    ]
+/// 在像素 (i, j) 内追踪从第 `first_sample` 个起的 `count` 个采样，以每个采样的图像坐标与颜色调用 `splat`；
+/// 每个采样使用由种子、像素与采样序号决定的 PCG 序列，结果与线程调度和分遍方式无关
+pub fn render_samples<F: FnMut((f64, f64), Color)>(
+    config: &Config,
+    sampler: &mut dyn Sampler,
+    i: u32,
+    j: u32,
+    first_sample: u32,
+    count: u32,
+    mut splat: F,
+) {
+    for index in first_sample..first_sample + count {
+        seed_random_stream(
+            config.seed,
+            hash_values(&[i as u64, j as u64, index as u64]),
+        );
+        sampler.start_pixel_sample((i, j), index);
+        let offset = sampler.get_2d();
+        let position = (i as f64 + offset.0, j as f64 + offset.1);
+        let (u, v) = (
+            position.0 / (config.image_width - 1) as f64,
+            position.1 / (config.image_height - 1) as f64,
+        );
+
+        let ray = config.camera.get_ray_upper_left(u, v, sampler);
+
+        splat(position, ray_color(ray, config, sampler));
+    }
 }
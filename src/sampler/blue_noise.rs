@@ -0,0 +1,111 @@
+use std::sync::OnceLock;
+
+use crate::*;
+
+use super::SampleState;
+
+/// 蓝噪声掩码的边长
+pub const BLUE_NOISE_SIZE: u32 = 64;
+
+/// 像素内使用秩 1 格点（一维为黄金比例序列，二维为 R2 序列），每个像素按蓝噪声掩码做 Cranley–Patterson 平移
+///
+/// 相邻像素的平移量差异大，同样采样数下误差在画面上表现为高频噪声；
+/// 各维度使用掩码的不同偏移并打乱采样序号，避免维度间相关
+#[derive(Debug, Clone, Copy)]
+pub struct BlueNoiseSampler {
+    samples_per_pixel: u32,
+    seed: u64,
+    state: SampleState,
+}
+
+impl BlueNoiseSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> BlueNoiseSampler {
+        BlueNoiseSampler {
+            samples_per_pixel: samples_per_pixel.max(1),
+            seed,
+            state: SampleState::default(),
+        }
+    }
+
+    // 取用 `count` 个维度，返回打乱后的采样序号与两个分量的平移量
+    fn index_and_shift(&mut self, count: u32) -> (u32, (f64, f64)) {
+        let dimension = self.state.take(count);
+        let hash = self.state.hash(dimension, self.seed);
+        let index = permutation_element(self.state.index, self.samples_per_pixel, hash as u32);
+        // 掩码的偏移只取决于维度，保持相邻像素间的蓝噪声关系
+        let offset = mix_bits(dimension as u64 ^ self.seed.rotate_left(17));
+        let x = self.state.pixel.0.wrapping_add(offset as u32);
+        let y = self.state.pixel.1.wrapping_add((offset >> 32) as u32);
+        // 第二个分量取掩码中相隔半个周期处的值
+        let half = BLUE_NOISE_SIZE / 2;
+        let shift = (
+            blue_noise(x, y),
+            blue_noise(x.wrapping_add(half), y.wrapping_add(half)),
+        );
+        (index, shift)
+    }
+}
+
+// 黄金比例与塑性数的倒数
+const R1: f64 = 0.618_033_988_749_895;
+const R2: (f64, f64) = (0.754_877_666_246_693, 0.569_840_290_998_053);
+
+impl Sampler for BlueNoiseSampler {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.state.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let (index, shift) = self.index_and_shift(1);
+        (0.5 + R1 * index as f64 + shift.0).fract()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let (index, shift) = self.index_and_shift(2);
+        (
+            (0.5 + R2.0 * index as f64 + shift.0).fract(),
+            (0.5 + R2.1 * index as f64 + shift.1).fract(),
+        )
+    }
+}
+
+/// 平铺的蓝噪声掩码在 (x, y) 处的值，[0, 1) 内均匀分布
+pub fn blue_noise(x: u32, y: u32) -> f64 {
+    let mask = BLUE_NOISE_MASK.get_or_init(generate_blue_noise_mask);
+    mask[((y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE) as usize]
+}
+
+static BLUE_NOISE_MASK: OnceLock<Vec<f64>> = OnceLock::new();
+
+// void-and-cluster 的填充阶段：每次在周期性高斯能量最低处（最大的空洞）放一个点，放入的次序即为秩
+fn generate_blue_noise_mask() -> Vec<f64> {
+    const SIGMA: f64 = 1.5;
+    let size = BLUE_NOISE_SIZE as usize;
+    let count = size * size;
+
+    // 环面上各偏移的高斯权重
+    let kernel: Vec<f64> = (0..count)
+        .map(|i| {
+            let (dx, dy) = (i % size, i / size);
+            let (dx, dy) = (dx.min(size - dx) as f64, dy.min(size - dy) as f64);
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect();
+
+    let mut energy = vec![0.0f64; count];
+    let mut rank = vec![f64::NAN; count];
+    for order in 0..count {
+        let void = (0..count)
+            .filter(|&i| rank[i].is_nan())
+            .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap_or_default();
+        rank[void] = (order as f64 + 0.5) / count as f64;
+
+        let (vx, vy) = (void % size, void / size);
+        for (i, value) in energy.iter_mut().enumerate() {
+            let (dx, dy) = ((i % size + size - vx) % size, (i / size + size - vy) % size);
+            *value += kernel[dy * size + dx];
+        }
+    }
+    rank
+}
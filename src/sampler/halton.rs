@@ -0,0 +1,74 @@
+use crate::*;
+
+use super::{SampleState, ONE_MINUS_EPSILON};
+
+// 各维度的底数，超出后退化为由哈希得到的独立采样
+const PRIMES: [u64; 64] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311,
+];
+
+/// Halton 序列：第 d 维为采样序号在第 d 个素数底下的根式反演，每个像素与维度各自做 Owen 扰乱
+#[derive(Debug, Clone, Copy)]
+pub struct HaltonSampler {
+    seed: u64,
+    state: SampleState,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> HaltonSampler {
+        HaltonSampler {
+            seed,
+            state: SampleState::default(),
+        }
+    }
+
+    fn sample_dimension(&self, dimension: u32) -> f64 {
+        match PRIMES.get(dimension as usize) {
+            Some(&base) => owen_scrambled_radical_inverse(
+                base,
+                self.state.index as u64,
+                self.state.hash(dimension, self.seed),
+            ),
+            None => self.state.random(dimension, self.seed),
+        }
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.state.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let dimension = self.state.take(1);
+        self.sample_dimension(dimension)
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let dimension = self.state.take(2);
+        (
+            self.sample_dimension(dimension),
+            self.sample_dimension(dimension + 1),
+        )
+    }
+}
+
+/// `index` 在 `base` 底下的根式反演，每一位数字按已生成的高位哈希出的排列置换，
+/// 直到剩余的位超出 f64 的精度
+pub fn owen_scrambled_radical_inverse(base: u64, mut index: u64, hash: u64) -> f64 {
+    let inverse_base = 1.0 / base as f64;
+    let mut inverse_base_power = 1.0;
+    let mut reversed: u64 = 0;
+    while 1.0 - (base - 1) as f64 * inverse_base_power < 1.0 {
+        let digit = index % base;
+        let digit_hash = mix_bits(hash ^ reversed);
+        let digit = permutation_element(digit as u32, base as u32, digit_hash as u32) as u64;
+        reversed = reversed * base + digit;
+        inverse_base_power *= inverse_base;
+        index /= base;
+    }
+    (reversed as f64 * inverse_base_power).min(ONE_MINUS_EPSILON)
+}
@@ -0,0 +1,23 @@
+use crate::*;
+
+/// 直接取线程的随机数生成器，各个采样与维度相互独立
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndependentSampler;
+
+impl IndependentSampler {
+    pub fn new() -> IndependentSampler {
+        IndependentSampler
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_pixel_sample(&mut self, _pixel: (u32, u32), _index: u32) {}
+
+    fn get_1d(&mut self) -> f64 {
+        random_01()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (random_01(), random_01())
+    }
+}
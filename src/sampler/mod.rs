@@ -0,0 +1,200 @@
+pub mod blue_noise;
+pub mod halton;
+pub mod independent;
+pub mod sobol;
+pub mod stratified;
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::*;
+
+/// 为每个像素采样依次提供 [0, 1) 内的随机数，按维度编号取用
+///
+/// 同一个采样中相机、材质与积分器按固定顺序取数：像素内偏移、镜头、快门时刻，之后是每次弹射的散射
+///
+/// 参与介质的自由程与碰撞判定在 `Hittable::hit` 中随遍历进行，所需随机数的个数不定，无法分配固定的维度，
+/// 因此按设计取自渲染器为每个采样重置的 PCG 序列（见 `seed_random_stream`），是白噪声维度
+pub trait Sampler {
+    /// 开始像素 `pixel` 的第 `index` 个采样，维度从零重新计数
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32);
+
+    fn get_1d(&mut self) -> f64;
+
+    fn get_2d(&mut self) -> (f64, f64);
+}
+
+/// 采样器的种类
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerKind {
+    /// 相互独立的均匀随机数（白噪声）
+    Independent,
+    /// 每一维分层抖动，各维度的层随机配对
+    Stratified,
+    /// Owen 扰乱的 Halton 序列，超出素数表的维度退化为独立采样
+    Halton,
+    /// Owen 扰乱的二维 Sobol 序列，按维度随机打乱采样顺序
+    #[default]
+    Sobol,
+    /// 像素内为秩 1 格点，像素间按蓝噪声掩码平移，误差呈蓝噪声分布
+    BlueNoise,
+}
+
+impl SamplerKind {
+    /// 每像素 `samples_per_pixel` 个采样的采样器，`seed` 决定扰乱方式
+    pub fn create(&self, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            SamplerKind::Independent => Box::new(IndependentSampler::new()),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(samples_per_pixel, seed)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(samples_per_pixel, seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoiseSampler::new(samples_per_pixel, seed)),
+        }
+    }
+}
+
+impl FromStr for SamplerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "independent" => Ok(SamplerKind::Independent),
+            "stratified" => Ok(SamplerKind::Stratified),
+            "halton" => Ok(SamplerKind::Halton),
+            "sobol" => Ok(SamplerKind::Sobol),
+            "blue_noise" => Ok(SamplerKind::BlueNoise),
+            _ => Err(format!(
+                "unknown sampler `{}` (expected independent, stratified, halton, sobol or blue_noise)",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for SamplerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SamplerKind::Independent => "independent",
+            SamplerKind::Stratified => "stratified",
+            SamplerKind::Halton => "halton",
+            SamplerKind::Sobol => "sobol",
+            SamplerKind::BlueNoise => "blue_noise",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// 当前像素、采样序号与下一个可用的维度
+#[derive(Debug, Default, Clone, Copy)]
+struct SampleState {
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32,
+}
+
+impl SampleState {
+    fn start(&mut self, pixel: (u32, u32), index: u32) {
+        *self = SampleState {
+            pixel,
+            index,
+            dimension: 0,
+        };
+    }
+
+    // 取用 `count` 个维度，返回第一个的编号
+    fn take(&mut self, count: u32) -> u32 {
+        let dimension = self.dimension;
+        self.dimension += count;
+        dimension
+    }
+
+    // 由像素、采样序号、维度与种子哈希出的 [0, 1) 内的均匀随机数，用于分层内的抖动等不需要低差异的维度
+    fn random(&self, dimension: u32, seed: u64) -> f64 {
+        let hash = hash_values(&[
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            self.index as u64,
+            dimension as u64,
+            seed,
+        ]);
+        (hash >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // 由像素、维度与种子决定的哈希，同一像素的各个采样相同
+    fn hash(&self, dimension: u32, seed: u64) -> u64 {
+        hash_values(&[
+            self.pixel.0 as u64,
+            self.pixel.1 as u64,
+            dimension as u64,
+            seed,
+        ])
+    }
+}
+
+/// 64 位整数的混合函数（SplitMix64 的末段）
+pub fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5_d329_728e_a185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81da_def4_bc2d_d44d);
+    v ^ (v >> 33)
+}
+
+pub fn hash_values(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e37_79b9_7f4a_7c15, |hash, value| {
+        mix_bits(hash ^ value.wrapping_add(0x9e37_79b9_7f4a_7c15))
+    })
+}
+
+/// `0..length` 的一个由 `seed` 决定的伪随机排列中第 `index` 个元素（Kensler 的哈希排列）
+pub fn permutation_element(mut index: u32, length: u32, seed: u32) -> u32 {
+    let length = length.max(1);
+    let mut mask = length - 1;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+    let p = seed;
+    loop {
+        index ^= p;
+        index = index.wrapping_mul(0xe170_893d);
+        index ^= p >> 16;
+        index ^= (index & mask) >> 4;
+        index ^= p >> 8;
+        index = index.wrapping_mul(0x0929_eb3f);
+        index ^= p >> 23;
+        index ^= (index & mask) >> 1;
+        index = index.wrapping_mul(1 | (p >> 27));
+        index = index.wrapping_mul(0x6935_fa69);
+        index ^= (index & mask) >> 11;
+        index = index.wrapping_mul(0x74dc_b303);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0x9e50_1cc3);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0xc860_a3df);
+        index &= mask;
+        index ^= index >> 5;
+        if index < length {
+            return index.wrapping_add(p) % length;
+        }
+    }
+}
+
+/// 基于哈希的 Owen 扰乱（Laine–Karras 排列），输入与输出都是 [0, 1) 的 32 位定点小数
+pub fn owen_scramble(v: u32, seed: u32) -> u32 {
+    let mut v = v.reverse_bits();
+    v ^= v.wrapping_mul(0x3d20_adea);
+    v = v.wrapping_add(seed);
+    v = v.wrapping_mul((seed >> 16) | 1);
+    v ^= v.wrapping_mul(0x0552_6c56);
+    v ^= v.wrapping_mul(0x53a2_2864);
+    v.reverse_bits()
+}
+
+// 小于 1 的最大 f64
+const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+// 32 位定点小数转为 [0, 1) 的浮点数
+fn fixed_to_unit(v: u32) -> f64 {
+    (v as f64 / 4294967296.0).min(ONE_MINUS_EPSILON)
+}
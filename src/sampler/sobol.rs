@@ -0,0 +1,71 @@
+use crate::*;
+
+use super::{fixed_to_unit, SampleState};
+
+/// Sobol 序列的前两维构成 (0, 2) 序列，每两个维度使用一组独立的 Owen 扰乱，
+/// 采样序号再按维度哈希出的排列打乱以消除维度间的相关
+///
+/// spp 为 2 的幂时每一对维度上的采样都是分层良好的 (0, 2) 网
+#[derive(Debug, Clone, Copy)]
+pub struct SobolSampler {
+    samples_per_pixel: u32,
+    seed: u64,
+    state: SampleState,
+}
+
+impl SobolSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> SobolSampler {
+        SobolSampler {
+            samples_per_pixel: samples_per_pixel.max(1),
+            seed,
+            state: SampleState::default(),
+        }
+    }
+
+    fn permuted_index(&self, hash: u64) -> u32 {
+        permutation_element(self.state.index, self.samples_per_pixel, hash as u32)
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.state.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let dimension = self.state.take(1);
+        let hash = self.state.hash(dimension, self.seed);
+        let index = self.permuted_index(hash);
+        fixed_to_unit(owen_scramble(sobol_first(index), (hash >> 32) as u32))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let dimension = self.state.take(2);
+        let hash = self.state.hash(dimension, self.seed);
+        let index = self.permuted_index(hash);
+        let scramble = mix_bits(hash);
+        (
+            fixed_to_unit(owen_scramble(sobol_first(index), scramble as u32)),
+            fixed_to_unit(owen_scramble(sobol_second(index), (scramble >> 32) as u32)),
+        )
+    }
+}
+
+/// Sobol 序列第一维，即以 2 为底的 van der Corput 序列（32 位定点小数）
+pub fn sobol_first(index: u32) -> u32 {
+    index.reverse_bits()
+}
+
+/// Sobol 序列第二维（32 位定点小数）
+pub fn sobol_second(mut index: u32) -> u32 {
+    let mut direction = 1u32 << 31;
+    let mut result = 0;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= direction;
+        }
+        index >>= 1;
+        direction ^= direction >> 1;
+    }
+    result
+}
@@ -0,0 +1,56 @@
+use crate::*;
+
+use super::SampleState;
+
+/// 分层抖动采样：一维分为 spp 层，二维分为 spp 个尽量接近正方形的格子，每个采样落在不同的层内随机位置
+///
+/// 每一维的层按像素与维度哈希出的排列分配给各个采样，避免维度之间的相关；层内的抖动同样由哈希得到
+#[derive(Debug, Clone, Copy)]
+pub struct StratifiedSampler {
+    samples_per_pixel: u32,
+    // 二维网格的列数，取不超过 spp 平方根的最大约数，使格子数恰好为 spp
+    columns: u32,
+    seed: u64,
+    state: SampleState,
+}
+
+impl StratifiedSampler {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> StratifiedSampler {
+        let samples_per_pixel = samples_per_pixel.max(1);
+        let columns = (1..=(samples_per_pixel as f64).sqrt() as u32)
+            .rev()
+            .find(|columns| samples_per_pixel.is_multiple_of(*columns))
+            .unwrap_or(1);
+        StratifiedSampler {
+            samples_per_pixel,
+            columns,
+            seed,
+            state: SampleState::default(),
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.state.start(pixel, index);
+    }
+
+    fn get_1d(&mut self) -> f64 {
+        let dimension = self.state.take(1);
+        let hash = self.state.hash(dimension, self.seed) as u32;
+        let stratum = permutation_element(self.state.index, self.samples_per_pixel, hash);
+        (stratum as f64 + self.state.random(dimension, self.seed)) / self.samples_per_pixel as f64
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let dimension = self.state.take(2);
+        let hash = self.state.hash(dimension, self.seed) as u32;
+        let (columns, rows) = (self.columns, self.samples_per_pixel / self.columns);
+        let stratum = permutation_element(self.state.index, self.samples_per_pixel, hash);
+        (
+            ((stratum % columns) as f64 + self.state.random(dimension, self.seed)) / columns as f64,
+            ((stratum / columns) as f64 + self.state.random(dimension + 1, self.seed))
+                / rows as f64,
+        )
+    }
+}
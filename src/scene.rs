@@ -1,24 +1,114 @@
 use crate::*;
 
+/// 场景分两层加速：`bvh` 为顶层 BVH，其图元是 `objects` 与各实例；
+/// 实例引用的底层 BVH（BLAS）在多个实例间共享，移动实例只需重建顶层
 pub struct Scene {
     pub objects: Vec<ObjectType>,
+    instances: Vec<Instance>,
+    // 上次构建或重新拟合顶层 BVH 之后移动过的实例
+    moved: Vec<usize>,
     pub bvh: BVH,
+    pub background: BackgroundType,
 }
 
-impl Scene {
-    pub fn new() -> Scene {
+// 对共享底层 BVH 的一次引用
+struct Instance {
+    blas: ObjectType,
+    matrix: Mat4,
+    transform: ObjectType,
+}
+
+impl Instance {
+    fn new(blas: ObjectType, matrix: Mat4) -> Option<Instance> {
+        let transform = Transform::new(blas.clone(), matrix)?;
+        Some(Instance {
+            blas,
+            matrix,
+            transform: Arc::new(Box::new(transform)),
+        })
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
         Scene {
             objects: Vec::new(),
+            instances: Vec::new(),
+            moved: Vec::new(),
             bvh: BVH::default(),
+            background: Arc::new(Box::new(GradientBackground::default())),
         }
     }
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Self::default()
+    }
 
     pub fn add_object(&mut self, object: ObjectType) {
         self.objects.push(object);
     }
 
+    pub fn set_background(&mut self, background: BackgroundType) {
+        self.background = background;
+    }
+
+    /// 以 `matrix` 放置一份底层 BVH（通常由 `BVH::build` 构建的网格），返回实例编号；
+    /// 矩阵不可逆时返回 `None`
+    pub fn add_instance(&mut self, blas: ObjectType, matrix: Mat4) -> Option<usize> {
+        self.instances.push(Instance::new(blas, matrix)?);
+        Some(self.instances.len() - 1)
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn instance_matrix(&self, id: usize) -> Mat4 {
+        self.instances[id].matrix
+    }
+
+    /// 移动实例，底层 BVH 保持不变；需再调用 `refit_bvh` 或 `build_bvh` 更新顶层。
+    /// 矩阵不可逆时返回 `None` 且不做修改
+    pub fn set_instance_matrix(&mut self, id: usize, matrix: Mat4) -> Option<()> {
+        let blas = self.instances[id].blas.clone();
+        self.instances[id] = Instance::new(blas, matrix)?;
+        self.moved.push(id);
+        Some(())
+    }
+
+    /// 保持顶层 BVH 的结构，只更新移动过的实例的包围盒，适合逐帧动画；
+    /// 增删过物体或质量下降过多时完全重建，返回是否重建
+    pub fn refit_bvh(&mut self) -> bool {
+        if self.bvh.stats().primitives != self.objects.len() + self.instances.len() {
+            self.build_bvh();
+            return true;
+        }
+
+        for id in std::mem::take(&mut self.moved) {
+            self.bvh.replace(
+                self.objects.len() + id,
+                self.instances[id].transform.clone(),
+            );
+        }
+        self.bvh.refit()
+    }
+
+    /// 构建顶层 BVH，实例引用的底层 BVH 不会重建
     pub fn build_bvh(&mut self) {
-        self.bvh = BVH::build(self.objects.clone());
+        self.moved.clear();
+        let top_level = self
+            .objects
+            .iter()
+            .cloned()
+            .chain(
+                self.instances
+                    .iter()
+                    .map(|instance| instance.transform.clone()),
+            )
+            .collect();
+        self.bvh = BVH::build(top_level);
     }
 }
 
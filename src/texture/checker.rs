@@ -0,0 +1,37 @@
+use crate::*;
+
+/// 三维棋盘格，`scale` 为每个格子的边长
+pub struct Checker {
+    odd: TextureType,
+    even: TextureType,
+    scale: f64,
+}
+
+impl Checker {
+    pub fn new(odd: TextureType, even: TextureType, scale: f64) -> Checker {
+        Checker { odd, even, scale }
+    }
+
+    pub fn from_colors(odd: Color, even: Color, scale: f64) -> Checker {
+        Checker::new(
+            Arc::new(Box::new(SolidColor::new(odd))),
+            Arc::new(Box::new(SolidColor::new(even))),
+            scale,
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, hit_point: Point3) -> Color {
+        let cell: i64 = hit_point
+            .array()
+            .iter()
+            .map(|c| (c / self.scale).floor() as i64)
+            .sum();
+
+        match cell.rem_euclid(2) == 0 {
+            true => self.even.value(u, v, hit_point),
+            false => self.odd.value(u, v, hit_point),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use crate::*;
+
+use image::{codecs::hdr::HdrDecoder, ImageError, ImageFormat, Rgb32FImage};
+
+/// 纹理坐标超出 [0, 1] 时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    #[default]
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    Nearest,
+    #[default]
+    Bilinear,
+}
+
+pub struct ImageTexture {
+    image: Rgb32FImage,
+    wrap: WrapMode,
+    filter: FilterMode,
+}
+
+impl ImageTexture {
+    /// `image` 中存储的应为线性空间的颜色
+    pub fn new(image: Rgb32FImage, wrap: WrapMode, filter: FilterMode) -> ImageTexture {
+        ImageTexture {
+            image,
+            wrap,
+            filter,
+        }
+    }
+
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        wrap: WrapMode,
+        filter: FilterMode,
+    ) -> Result<ImageTexture, ImageError> {
+        Ok(ImageTexture::new(open_linear_image(path)?, wrap, filter))
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let wrap = |i: i64, size: i64| -> u32 {
+            let i = match self.wrap {
+                WrapMode::Repeat => i.rem_euclid(size),
+                WrapMode::Clamp => i.clamp(0, size - 1),
+                WrapMode::Mirror => {
+                    let i = i.rem_euclid(2 * size);
+                    match i < size {
+                        true => i,
+                        false => 2 * size - 1 - i,
+                    }
+                }
+            };
+            i as u32
+        };
+        let x = wrap(x, self.image.width() as i64);
+        let y = wrap(y, self.image.height() as i64);
+        let pixel = self.image.get_pixel(x, y);
+        Color::new_color(pixel[0] as f64, pixel[1] as f64, pixel[2] as f64)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _hit_point: Point3) -> Color {
+        // 纹理坐标 v 向上，图片行号向下
+        let x = u * self.image.width() as f64;
+        let y = (1.0 - v) * self.image.height() as f64;
+
+        match self.filter {
+            FilterMode::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            FilterMode::Bilinear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                self.texel(x0, y0) * ((1.0 - fx) * (1.0 - fy))
+                    + self.texel(x0 + 1, y0) * (fx * (1.0 - fy))
+                    + self.texel(x0, y0 + 1) * ((1.0 - fx) * fy)
+                    + self.texel(x0 + 1, y0 + 1) * (fx * fy)
+            }
+        }
+    }
+}
+
+/// 读取图片并转换为线性空间的浮点颜色：Radiance HDR 与 OpenEXR 本身为线性数据，其余格式按 sRGB 解码
+pub fn open_linear_image<P: AsRef<Path>>(path: P) -> Result<Rgb32FImage, ImageError> {
+    match ImageFormat::from_path(&path)? {
+        // `image::open` 会把 Radiance HDR 转换为 8 位图像，需直接读取浮点数据
+        ImageFormat::Hdr => {
+            let decoder = HdrDecoder::new(BufReader::new(File::open(&path)?))?;
+            let (width, height) = (decoder.metadata().width, decoder.metadata().height);
+            let pixels = decoder.read_image_hdr()?;
+            Ok(Rgb32FImage::from_fn(width, height, |x, y| {
+                pixels[(y * width + x) as usize]
+            }))
+        }
+        ImageFormat::OpenExr => Ok(image::open(path)?.into_rgb32f()),
+        _ => {
+            let mut image = image::open(path)?.into_rgb32f();
+            for channel in image.iter_mut() {
+                *channel = srgb_to_linear(*channel);
+            }
+            Ok(image)
+        }
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    match c <= 0.04045 {
+        true => c / 12.92,
+        false => ((c + 0.055) / 1.055).powf(2.4),
+    }
+}
@@ -0,0 +1,10 @@
+pub mod checker;
+pub mod image_texture;
+pub mod noise;
+pub mod solid;
+
+use crate::*;
+
+pub trait Texture {
+    fn value(&self, u: f64, v: f64, hit_point: Point3) -> Color;
+}
@@ -0,0 +1,133 @@
+use crate::*;
+
+const POINT_COUNT: usize = 256;
+
+/// Perlin 噪声，晶格点上存储随机单位向量
+pub struct Perlin {
+    random_vectors: Vec<Vec3>,
+    permutation: [Vec<usize>; 3],
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Perlin::new()
+    }
+}
+
+impl Perlin {
+    pub fn new() -> Perlin {
+        let random_vectors = (0..POINT_COUNT)
+            .map(|_| {
+                Vec3(
+                    random_range(-1.0, 1.0),
+                    random_range(-1.0, 1.0),
+                    random_range(-1.0, 1.0),
+                )
+                .unit_vector()
+            })
+            .collect();
+
+        Perlin {
+            random_vectors,
+            permutation: [
+                generate_permutation(),
+                generate_permutation(),
+                generate_permutation(),
+            ],
+        }
+    }
+
+    pub fn noise(&self, p: Point3) -> f64 {
+        let floor = p.array().map(f64::floor);
+        let [u, v, w] = [p.0 - floor[0], p.1 - floor[1], p.2 - floor[2]];
+        let [i, j, k] = floor.map(|c| c as i64);
+
+        let mut accum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.permutation[0][((i + di) & 255) as usize]
+                        ^ self.permutation[1][((j + dj) & 255) as usize]
+                        ^ self.permutation[2][((k + dk) & 255) as usize];
+                    let weight = Vec3(u - di as f64, v - dj as f64, w - dk as f64);
+
+                    // Hermite 平滑插值
+                    let fade = |t: f64, d: i64| {
+                        let t = t * t * (3.0 - 2.0 * t);
+                        d as f64 * t + (1.0 - d as f64) * (1.0 - t)
+                    };
+                    accum += fade(u, di)
+                        * fade(v, dj)
+                        * fade(w, dk)
+                        * Vec3::dot(self.random_vectors[index], weight);
+                }
+            }
+        }
+        accum
+    }
+
+    /// 多个倍频的噪声叠加
+    pub fn turbulence(&self, p: Point3, depth: u32) -> f64 {
+        let mut accum = 0.0;
+        let mut p = p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(p);
+            weight *= 0.5;
+            p *= 2.0;
+        }
+
+        accum.abs()
+    }
+}
+
+fn generate_permutation() -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+    for i in (1..POINT_COUNT).rev() {
+        let target = random_int(0, i as i32) as usize;
+        permutation.swap(i, target);
+    }
+    permutation
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoiseKind {
+    #[default]
+    Perlin,
+    Turbulence,
+    Marble,
+}
+
+pub struct NoiseTexture {
+    perlin: Perlin,
+    kind: NoiseKind,
+    scale: f64,
+    color: Color,
+}
+
+impl NoiseTexture {
+    pub fn new(kind: NoiseKind, scale: f64, color: Color) -> NoiseTexture {
+        NoiseTexture {
+            perlin: Perlin::new(),
+            kind,
+            scale,
+            color,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, hit_point: Point3) -> Color {
+        let p = hit_point * self.scale;
+        let intensity = match self.kind {
+            NoiseKind::Perlin => 0.5 * (1.0 + self.perlin.noise(p)),
+            NoiseKind::Turbulence => self.perlin.turbulence(p, 7),
+            // 条纹沿 z 方向，频率由 scale 控制，湍流扰动条纹的相位
+            NoiseKind::Marble => {
+                0.5 * (1.0 + (p.z() + 10.0 * self.perlin.turbulence(hit_point, 7)).sin())
+            }
+        };
+        self.color * intensity
+    }
+}
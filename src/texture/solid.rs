@@ -0,0 +1,18 @@
+use crate::*;
+
+#[derive(Default)]
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> SolidColor {
+        SolidColor { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _hit_point: Point3) -> Color {
+        self.color
+    }
+}
@@ -0,0 +1,127 @@
+use std::{f64::consts::PI, fmt::Display, str::FromStr};
+
+/// 图像中的一个矩形区块，是渲染任务调度的单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    pub fn pixel_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    /// 按行优先顺序遍历区块内像素的图像坐标
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> {
+        let tile = *self;
+        (tile.y..tile.y + tile.height)
+            .flat_map(move |y| (tile.x..tile.x + tile.width).map(move |x| (x, y)))
+    }
+}
+
+/// 区块的渲染顺序
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TileOrder {
+    /// 从上到下、从左到右
+    Scanline,
+    /// 从图像中心向外螺旋，先得到画面主体
+    #[default]
+    Spiral,
+    /// 沿 Hilbert 曲线，相邻任务访问相邻区域，缓存更友好
+    Hilbert,
+}
+
+impl FromStr for TileOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scanline" => Ok(TileOrder::Scanline),
+            "spiral" => Ok(TileOrder::Spiral),
+            "hilbert" => Ok(TileOrder::Hilbert),
+            _ => Err(format!(
+                "unknown tile order `{}` (expected scanline, spiral or hilbert)",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for TileOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TileOrder::Scanline => "scanline",
+            TileOrder::Spiral => "spiral",
+            TileOrder::Hilbert => "hilbert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 将 `width` x `height` 的图像切成边长为 `tile_size` 的区块（右、下边缘的区块可能更小），按 `order` 排序
+pub fn generate_tiles(width: u32, height: u32, tile_size: u32, order: TileOrder) -> Vec<Tile> {
+    let tile_size = tile_size.max(1);
+    let (columns, rows) = (width.div_ceil(tile_size), height.div_ceil(tile_size));
+
+    let mut cells: Vec<(u32, u32)> = (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column, row)))
+        .collect();
+    match order {
+        TileOrder::Scanline => {}
+        TileOrder::Spiral => {
+            // 按到中心区块的切比雪夫距离分环，环内按角度排序
+            let center = ((columns - 1) as f64 / 2.0, (rows - 1) as f64 / 2.0);
+            let key = |&(column, row): &(u32, u32)| {
+                let (dx, dy) = (column as f64 - center.0, row as f64 - center.1);
+                let ring = dx.abs().max(dy.abs()).round() as u32;
+                let angle = (dy.atan2(dx) + 2.0 * PI) % (2.0 * PI);
+                (ring, angle)
+            };
+            cells.sort_by(|a, b| {
+                let (a, b) = (key(a), key(b));
+                a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+            });
+        }
+        TileOrder::Hilbert => {
+            let side = columns.max(rows).next_power_of_two();
+            cells.sort_by_key(|&(column, row)| hilbert_index(side, column, row));
+        }
+    }
+
+    cells
+        .into_iter()
+        .map(|(column, row)| {
+            let (x, y) = (column * tile_size, row * tile_size);
+            Tile {
+                x,
+                y,
+                width: tile_size.min(width - x),
+                height: tile_size.min(height - y),
+            }
+        })
+        .collect()
+}
+
+// 边长为 `side`（2 的幂）的网格中 (x, y) 在 Hilbert 曲线上的序号
+fn hilbert_index(side: u32, mut x: u32, mut y: u32) -> u64 {
+    let mut index = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        index += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        // 旋转象限使子曲线首尾相接
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
+}
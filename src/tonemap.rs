@@ -0,0 +1,120 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::*;
+
+use image::Rgb;
+
+/// 把高动态范围的辐亮度压缩到 [0, 1] 的色调映射算子，逐通道作用
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapper {
+    /// 直接截断到 [0, 1]
+    #[default]
+    Clamp,
+    /// `c / (1 + c)`
+    Reinhard,
+    /// 以 `white_point` 为纯白的 Reinhard
+    ExtendedReinhard,
+    /// Narkowicz 对 ACES 电影曲线的拟合
+    Aces,
+    /// Hable 的 Uncharted 2 曲线
+    Hable,
+}
+
+impl FromStr for ToneMapper {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(ToneMapper::Clamp),
+            "reinhard" => Ok(ToneMapper::Reinhard),
+            "extended_reinhard" => Ok(ToneMapper::ExtendedReinhard),
+            "aces" => Ok(ToneMapper::Aces),
+            "hable" => Ok(ToneMapper::Hable),
+            _ => Err(format!(
+                "unknown tone mapper `{}` (expected clamp, reinhard, extended_reinhard, aces or hable)",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for ToneMapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ToneMapper::Clamp => "clamp",
+            ToneMapper::Reinhard => "reinhard",
+            ToneMapper::ExtendedReinhard => "extended_reinhard",
+            ToneMapper::Aces => "aces",
+            ToneMapper::Hable => "hable",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub const DEFAULT_WHITE_POINT: f64 = 4.0;
+
+/// 输出 8 位图像前的处理：曝光、色调映射与 sRGB 编码
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMapping {
+    pub operator: ToneMapper,
+    /// 曝光补偿（EV），每增加 1 亮度加倍
+    pub exposure: f64,
+    /// 扩展 Reinhard 映射为纯白的辐亮度
+    pub white_point: f64,
+}
+
+impl Default for ToneMapping {
+    fn default() -> Self {
+        ToneMapping {
+            operator: ToneMapper::default(),
+            exposure: 0.0,
+            white_point: DEFAULT_WHITE_POINT,
+        }
+    }
+}
+
+impl ToneMapping {
+    /// 线性辐亮度映射到 [0, 1] 的线性显示值
+    pub fn apply(&self, color: Color) -> Color {
+        let scale = self.exposure.exp2();
+        let map = |c: f64| {
+            let c = (c * scale).max(0.0);
+            match self.operator {
+                ToneMapper::Clamp => c,
+                ToneMapper::Reinhard => c / (1.0 + c),
+                ToneMapper::ExtendedReinhard => {
+                    c * (1.0 + c / (self.white_point * self.white_point)) / (1.0 + c)
+                }
+                ToneMapper::Aces => {
+                    // 拟合曲线针对的亮度约为本渲染器的 1/0.6 倍
+                    let c = c * 0.6;
+                    c * (2.51 * c + 0.03) / (c * (2.43 * c + 0.59) + 0.14)
+                }
+                ToneMapper::Hable => hable(2.0 * c) / hable(HABLE_WHITE),
+            }
+            .clamp(0.0, 1.0)
+        };
+        Color::new_color(map(color.0), map(color.1), map(color.2))
+    }
+
+    /// 色调映射后按 sRGB 曲线编码并量化为 8 位
+    pub fn to_rgb8(&self, color: Color) -> Rgb<u8> {
+        let color = self.apply(color);
+        Rgb([color.0, color.1, color.2].map(|c| (linear_to_srgb(c) * 255.0).round() as u8))
+    }
+}
+
+const HABLE_WHITE: f64 = 11.2;
+
+fn hable(x: f64) -> f64 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f
+}
+
+/// sRGB 传递函数（编码方向），输入为 [0, 1] 的线性值
+pub fn linear_to_srgb(c: f64) -> f64 {
+    match c <= 0.0031308 {
+        true => 12.92 * c,
+        false => 1.055 * c.powf(1.0 / 2.4) - 0.055,
+    }
+}
@@ -1,17 +1,36 @@
 use crate::*;
 
+use std::{cell::RefCell, f64::consts::PI};
+
 use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+thread_local! {
+    // 初始状态固定，未重置种子时各线程的随机序列也可复现
+    static RNG: RefCell<Pcg64> = RefCell::new(Pcg64::seed_from_u64(DEFAULT_SEED));
+}
+
+/// 重置当前线程的随机数生成器，使之后的随机序列可复现
+pub fn seed_random(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = Pcg64::seed_from_u64(seed));
+}
+
+/// 把当前线程的随机数生成器重置为由 `seed` 与 `stream` 决定的 PCG 序列，不同 `stream` 的序列互不重叠
+pub fn seed_random_stream(seed: u64, stream: u64) {
+    let state = (mix_bits(seed) as u128) << 64 | mix_bits(seed ^ stream) as u128;
+    RNG.with(|rng| *rng.borrow_mut() = Pcg64::new(state, stream as u128));
+}
 
 pub fn random_01() -> f64 {
-    rand::thread_rng().gen::<f64>()
+    RNG.with(|rng| rng.borrow_mut().gen::<f64>())
 }
 
 pub fn random_range(t_min: f64, t_max: f64) -> f64 {
-    rand::thread_rng().gen_range(t_min..=t_max)
+    RNG.with(|rng| rng.borrow_mut().gen_range(t_min..=t_max))
 }
 
 pub fn random_int(t_min: i32, t_max: i32) -> i32 {
-    rand::thread_rng().gen_range(t_min..=t_max)
+    RNG.with(|rng| rng.borrow_mut().gen_range(t_min..=t_max))
 }
 
 pub fn random_unit_sphere() -> Vec3 {
@@ -46,3 +65,38 @@ pub fn random_hemisphere(normal: Vec3) -> Vec3 {
         sample * -1.0
     }
 }
+
+/// 由 [0, 1)² 内的采样得到单位圆盘上的均匀点（同心映射，保持分层）
+pub fn sample_unit_disk(u: (f64, f64)) -> Vec3 {
+    let (x, y) = (2.0 * u.0 - 1.0, 2.0 * u.1 - 1.0);
+    if x == 0.0 && y == 0.0 {
+        return Vec3(0.0, 0.0, 0.0);
+    }
+    let (r, theta) = match x.abs() > y.abs() {
+        true => (x, PI / 4.0 * (y / x)),
+        false => (y, PI / 2.0 - PI / 4.0 * (x / y)),
+    };
+    Vec3(r * theta.cos(), r * theta.sin(), 0.0)
+}
+
+/// 由 [0, 1)² 内的采样得到单位球面上均匀分布的方向
+pub fn sample_unit_sphere(u: (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * u.0;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.1;
+    Vec3(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// 单位球内的均匀点，`radius` 为 [0, 1) 内决定半径的第三个采样
+pub fn sample_unit_ball(u: (f64, f64), radius: f64) -> Vec3 {
+    sample_unit_sphere(u) * radius.cbrt()
+}
+
+/// 以 `normal` 为轴的半球内均匀分布的方向
+pub fn sample_hemisphere(normal: Vec3, u: (f64, f64)) -> Vec3 {
+    let dir = sample_unit_sphere(u);
+    match Vec3::dot(dir, normal) < 0.0 {
+        true => dir * -1.0,
+        false => dir,
+    }
+}
@@ -100,10 +100,20 @@ impl Vec3 {
     pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: f64) -> Vec3 {
         let cos_theta = f64::min(Vec3::dot(uv * -1.0, n), 1.0);
         let r_out_perp = (uv + n * cos_theta) * etai_over_etat;
-        let r_out_parallel = n * (-1.0 * f64::sqrt((1.0 - r_out_perp.length_squared()).abs()));
+        let r_out_parallel = n * -f64::sqrt((1.0 - r_out_perp.length_squared()).abs());
         r_out_perp + r_out_parallel
     }
 
+    /// 以单位向量 `n` 为轴构造两条与之正交的单位向量
+    pub fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+        let helper = match n.0.abs() > 0.9 {
+            true => Vec3(0.0, 1.0, 0.0),
+            false => Vec3(1.0, 0.0, 0.0),
+        };
+        let tangent = Vec3::cross(helper, n).unit_vector();
+        (tangent, Vec3::cross(n, tangent))
+    }
+
     pub fn set_face_normal(ray_in_dir: Vec3, outward_normal: Vec3) -> (bool, Vec3) {
         let is_front_face = Vec3::dot(ray_in_dir, outward_normal) < 0.0;
         (
@@ -1,3 +1,7 @@
+#![allow(clippy::excessive_precision)]
+
+use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
+
 use rtweekend::*;
 
 #[test]
@@ -172,3 +176,1395 @@ fn random_unit_sphere_work() {
 fn bvh_work() {
     Config::new();
 }
+
+#[test]
+fn scene_file_work() {
+    let config = Config::from_file("scenes/three_spheres.toml").unwrap();
+    assert_eq!(config.file_path, "three_spheres.png");
+    assert_eq!((config.image_width, config.image_height), (400, 225));
+    assert_eq!(config.scene.objects.len(), 5);
+}
+
+#[test]
+fn scene_file_unknown_material() {
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [materials.red]
+        type = "lambertian"
+        albedo = [1.0, 0.0, 0.0]
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, 0.0]
+        radius = 1.0
+        material = "blue"
+    "#;
+    let message = parse_config(text).err().unwrap().to_string();
+    assert_eq!(message, "`objects[0]`: unknown material `blue`");
+}
+
+#[test]
+fn rect_hit_work() {
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let rect = AxisAlignedRect::xz((-1.0, 1.0), (-1.0, 1.0), 0.0, material);
+    let ray = Ray::new(Vec3(0.5, 2.0, 0.5), Vec3(0.0, -1.0, 0.0), 10);
+    let record = rect.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.t, 2.0);
+    assert_eq!(record.hit_normal, Vec3(0.0, 1.0, 0.0));
+    assert!(record.front_face);
+
+    let miss = Ray::new(Vec3(1.5, 2.0, 0.5), Vec3(0.0, -1.0, 0.0), 10);
+    assert!(rect.hit(&miss, (1e-8, f64::INFINITY)).is_none());
+
+    let aabb = rect.bounding_box();
+    assert!(aabb.max().y() - aabb.min().y() > 0.0);
+
+    // 与矩形平行的光线不相交，不会在无穷远处得到 NaN 交点
+    let grazing = Ray::new(Vec3(-5.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0), 10);
+    assert!(rect.hit(&grazing, (1e-8, f64::INFINITY)).is_none());
+    let degenerate = Ray::new(Vec3(0.0, -1.0, 0.0), Vec3(0.0, 0.0, 0.0), 10);
+    assert!(rect.hit(&degenerate, (1e-8, f64::INFINITY)).is_none());
+}
+
+#[test]
+fn quad_hit_work() {
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let quad = Quad::new(
+        Vec3(0.0, 0.0, 0.0),
+        Vec3(2.0, 0.0, 0.0),
+        Vec3(1.0, 1.0, 0.0),
+        material,
+    );
+    let ray = Ray::new(Vec3(1.5, 0.5, -1.0), Vec3(0.0, 0.0, 1.0), 10);
+    let record = quad.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.t, 1.0);
+    assert!(!record.front_face);
+    assert_eq!(record.hit_normal, Vec3(0.0, 0.0, -1.0));
+
+    let miss = Ray::new(Vec3(0.2, 0.9, -1.0), Vec3(0.0, 0.0, 1.0), 10);
+    assert!(quad.hit(&miss, (1e-8, f64::INFINITY)).is_none());
+}
+
+#[test]
+fn obj_mesh_work() {
+    let text = "
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vn 0 0 1
+        usemtl red
+        f 1//1 2//1 3//1 4//1
+        usemtl blue
+        f -4 -2 -1
+    ";
+    let mesh = Arc::new(parse_obj(text).unwrap());
+    assert_eq!(mesh.triangles().len(), 3);
+    assert_eq!(mesh.group(0), Some("red"));
+    assert_eq!(mesh.group(1), Some("red"));
+    assert_eq!(mesh.group(2), Some("blue"));
+    assert_eq!(mesh.triangles()[2][2].position, 3);
+
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let triangles = mesh.objects(material, &std::collections::HashMap::new());
+    let ray = Ray::new(Vec3(0.75, 0.25, 2.0), Vec3(0.0, 0.0, -1.0), 10);
+    let record = triangles[0].hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.t, 2.0);
+    assert!(record.front_face);
+    assert!(triangles[1].hit(&ray, (1e-8, f64::INFINITY)).is_none());
+
+    assert!(parse_obj("v 0 0 0\nf 1 2 3\n").is_err());
+
+    // 常见导出器写出的其余语句被忽略
+    let exported = "
+        mtllib scene.mtl
+        v 0 0 0
+        v 1 0 0
+        v 0 1 0
+        vp 0.5
+        cstype bspline
+        usemap none
+        mg 1
+        shadow_obj shadow.obj
+        f 1 2 3
+    ";
+    assert_eq!(parse_obj(exported).unwrap().triangles().len(), 1);
+}
+
+#[test]
+fn diffuse_light_work() {
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [materials.light]
+        type = "diffuse_light"
+        emit = [1.0, 2.0, 3.0]
+        intensity = 2.0
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, -5.0]
+        radius = 1.0
+        material = "light"
+    "#;
+    let config = parse_config(text).unwrap();
+    let ray = Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, -1.0), 10);
+    assert_eq!(
+        ray_color(ray, &config, &mut IndependentSampler::new()),
+        Color::new_color(2.0, 4.0, 6.0)
+    );
+}
+
+#[test]
+fn environment_map_work() {
+    // 左半边红色、右半边蓝色
+    let image = image::Rgb32FImage::from_fn(8, 4, |x, _| match x < 4 {
+        true => image::Rgb([1.0, 0.0, 0.0]),
+        false => image::Rgb([0.0, 0.0, 1.0]),
+    });
+    let map = EnvironmentMap::new(image.clone(), 0.0, 2.0);
+    assert_eq!(
+        map.radiance(Vec3(-1.0, 0.0, 0.0)),
+        Color::new_color(2.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        map.radiance(Vec3(1.0, 0.0, 0.0)),
+        Color::new_color(0.0, 0.0, 2.0)
+    );
+
+    let rotated = EnvironmentMap::new(image, 180.0, 1.0);
+    assert_eq!(
+        rotated.radiance(Vec3(-1.0, 0.0, 0.0)),
+        Color::new_color(0.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn solid_background_work() {
+    let mut scene = Scene::new();
+    scene.set_background(Arc::new(Box::new(SolidBackground::new(Color::new_color(
+        0.1, 0.2, 0.3,
+    )))));
+    assert_eq!(
+        scene.background.radiance(Vec3(0.0, 1.0, 0.0)),
+        Color::new_color(0.1, 0.2, 0.3)
+    );
+}
+
+#[test]
+fn checker_texture_work() {
+    let checker = Checker::from_colors(
+        Color::new_color(0.0, 0.0, 0.0),
+        Color::new_color(1.0, 1.0, 1.0),
+        0.5,
+    );
+    assert_eq!(
+        checker.value(0.0, 0.0, Vec3(0.25, 0.25, 0.25)),
+        Color::new_color(1.0, 1.0, 1.0)
+    );
+    assert_eq!(
+        checker.value(0.0, 0.0, Vec3(0.75, 0.25, 0.25)),
+        Color::new_color(0.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        checker.value(0.0, 0.0, Vec3(-0.25, 0.25, 0.25)),
+        Color::new_color(0.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn image_texture_work() {
+    // 2x1 图片：左黑右白
+    let image = image::Rgb32FImage::from_fn(2, 1, |x, _| image::Rgb([x as f32; 3]));
+    let nearest = ImageTexture::new(image.clone(), WrapMode::Repeat, FilterMode::Nearest);
+    assert_eq!(
+        nearest.value(0.25, 0.5, Vec3::default()),
+        Vec3(0.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        nearest.value(1.75, 0.5, Vec3::default()),
+        Vec3(1.0, 1.0, 1.0)
+    );
+
+    let clamp = ImageTexture::new(image.clone(), WrapMode::Clamp, FilterMode::Nearest);
+    assert_eq!(clamp.value(-3.0, 0.5, Vec3::default()), Vec3(0.0, 0.0, 0.0));
+
+    let mirror = ImageTexture::new(image.clone(), WrapMode::Mirror, FilterMode::Nearest);
+    assert_eq!(
+        mirror.value(1.25, 0.5, Vec3::default()),
+        Vec3(1.0, 1.0, 1.0)
+    );
+
+    let bilinear = ImageTexture::new(image, WrapMode::Clamp, FilterMode::Bilinear);
+    assert_eq!(
+        bilinear.value(0.5, 0.5, Vec3::default()),
+        Vec3(0.5, 0.5, 0.5)
+    );
+}
+
+#[test]
+fn noise_texture_work() {
+    let noise = NoiseTexture::new(NoiseKind::Perlin, 4.0, Color::new_color(1.0, 1.0, 1.0));
+    for _ in 0..100 {
+        let p = Vec3(
+            random_range(-10.0, 10.0),
+            random_range(-10.0, 10.0),
+            random_range(-10.0, 10.0),
+        );
+        let value = noise.value(0.0, 0.0, p);
+        assert!(value.r() >= 0.0 && value.r() <= 1.0);
+    }
+}
+
+#[test]
+fn scene_file_texture_work() {
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [textures.checker]
+        type = "checker"
+        odd = "dark"
+        even = [0.9, 0.9, 0.9]
+
+        [textures.dark]
+        type = "solid"
+        color = [0.1, 0.1, 0.1]
+
+        [materials.floor]
+        type = "lambertian"
+        albedo = "checker"
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, 0.0]
+        radius = 1.0
+        material = "floor"
+    "#;
+    assert!(parse_config(text).is_ok());
+
+    let cyclic = text.replace("odd = \"dark\"", "odd = \"checker\"");
+    let message = parse_config(&cyclic).err().unwrap().to_string();
+    assert_eq!(
+        message,
+        "`textures.checker`: texture `checker` references itself"
+    );
+}
+
+#[test]
+fn hit_record_uv_work() {
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+
+    let sphere = Sphere::new(Vec3(0.0, 0.0, 0.0), 2.0, material.clone());
+    let ray = Ray::new(Vec3(5.0, 0.0, 0.0), Vec3(-1.0, 0.0, 0.0), 10);
+    let record = sphere.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!((record.u, record.v), (0.5, 0.5));
+    assert_eq!(record.tangent, Vec3(0.0, 0.0, -1.0));
+    assert_eq!(record.bitangent, Vec3(0.0, 1.0, 0.0));
+
+    let quad = Quad::new(
+        Vec3(0.0, 0.0, 0.0),
+        Vec3(4.0, 0.0, 0.0),
+        Vec3(0.0, 2.0, 0.0),
+        material.clone(),
+    );
+    let ray = Ray::new(Vec3(1.0, 1.5, 1.0), Vec3(0.0, 0.0, -1.0), 10);
+    let record = quad.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!((record.u, record.v), (0.25, 0.75));
+    assert_eq!(record.tangent, Vec3(1.0, 0.0, 0.0));
+
+    let mesh = Arc::new(
+        parse_obj(
+            "
+            v 0 0 0
+            v 2 0 0
+            v 0 2 0
+            vt 0 0
+            vt 0 1
+            vt 1 0
+            f 1/1 2/2 3/3
+            ",
+        )
+        .unwrap(),
+    );
+    let triangle = Triangle::new(mesh, 0, material.clone());
+    let ray = Ray::new(Vec3(0.5, 1.0, 1.0), Vec3(0.0, 0.0, -1.0), 10);
+    let record = triangle.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!((record.u, record.v), (0.5, 0.25));
+    assert_eq!(record.tangent, Vec3(0.0, 1.0, 0.0));
+    assert_eq!(record.bitangent, Vec3(1.0, 0.0, 0.0));
+
+    // 球面两极的参数切线退化，改用由法线生成的切线框架
+    let ray = Ray::new(Vec3(0.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0), 10);
+    let record = sphere.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert!((record.tangent.length() - 1.0).abs() < 1e-12);
+    assert!(Vec3::dot(record.tangent, record.hit_normal).abs() < 1e-12);
+    assert!(Vec3::dot(record.tangent, record.bitangent).abs() < 1e-12);
+
+    // 介质内的击中没有表面，切线为零向量
+    let boundary: ObjectType = Arc::new(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0, material)));
+    let fog = ConstantMedium::new(boundary, 1e6, Color::new_color(1.0, 1.0, 1.0));
+    let ray = Ray::new(Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+    let record = fog.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.tangent, Vec3::default());
+}
+
+#[test]
+fn mat4_inverse_work() {
+    let m = Mat4::translate(Vec3(1.0, 2.0, 3.0))
+        * Mat4::rotate_y(30.0)
+        * Mat4::scale(Vec3(2.0, 3.0, 4.0));
+    let product = m * m.inverse().unwrap();
+    for i in 0..4 {
+        for j in 0..4 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((product.0[i][j] - expected).abs() < 1e-12);
+        }
+    }
+    assert!(Mat4::scale(Vec3(1.0, 0.0, 1.0)).inverse().is_none());
+
+    let p = Mat4::rotate_z(90.0).transform_point(Vec3(1.0, 0.0, 0.0));
+    assert!((p - Vec3(0.0, 1.0, 0.0)).near_zero());
+}
+
+#[test]
+fn transform_hit_work() {
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let sphere: ObjectType = Arc::new(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0, material)));
+    let matrix = Mat4::translate(Vec3(0.0, 0.0, -10.0)) * Mat4::scale(Vec3(2.0, 1.0, 1.0));
+    let instance = Transform::new(sphere, matrix).unwrap();
+
+    let aabb = instance.bounding_box();
+    assert_eq!(aabb.min(), Vec3(-2.0, -1.0, -11.0));
+    assert_eq!(aabb.max(), Vec3(2.0, 1.0, -9.0));
+
+    let ray = Ray::new(Vec3(5.0, 0.0, -10.0), Vec3(-1.0, 0.0, 0.0), 10);
+    let record = instance.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.t, 3.0);
+    assert_eq!(record.hit_point, Vec3(2.0, 0.0, -10.0));
+    assert_eq!(record.hit_normal, Vec3(1.0, 0.0, 0.0));
+    assert!(record.front_face);
+}
+
+#[test]
+fn motion_blur_work() {
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let sphere = MovingSphere::new(
+        Vec3(0.0, 0.0, -5.0),
+        Vec3(4.0, 0.0, -5.0),
+        (0.0, 1.0),
+        1.0,
+        material.clone(),
+    );
+    assert_eq!(sphere.center(0.5), Vec3(2.0, 0.0, -5.0));
+    assert_eq!(sphere.bounding_box().min(), Vec3(-1.0, -1.0, -6.0));
+    assert_eq!(sphere.bounding_box().max(), Vec3(5.0, 1.0, -4.0));
+
+    let ray = Ray::new(Vec3(4.0, 0.0, 0.0), Vec3(0.0, 0.0, -1.0), 10);
+    assert!(sphere.hit(&ray, (1e-8, f64::INFINITY)).is_none());
+    assert!(sphere
+        .hit(&ray.with_time(1.0), (1e-8, f64::INFINITY))
+        .is_some());
+
+    let unit_sphere: ObjectType =
+        Arc::new(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0, material)));
+    let end = Keyframe {
+        translate: Vec3(0.0, 3.0, 0.0),
+        ..Default::default()
+    };
+    let instance = MotionTransform::new(unit_sphere, Keyframe::default(), end, (0.0, 1.0)).unwrap();
+    let aabb = instance.bounding_box();
+    assert!(aabb.min().y() <= -1.0 && aabb.max().y() >= 4.0);
+
+    let ray = Ray::new(Vec3(0.0, 3.0, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+    assert!(instance.hit(&ray, (1e-8, f64::INFINITY)).is_none());
+    let record = instance
+        .hit(&ray.with_time(1.0), (1e-8, f64::INFINITY))
+        .unwrap();
+    assert!((record.hit_point - Vec3(0.0, 3.0, 1.0)).near_zero());
+}
+
+#[test]
+fn scene_file_motion_work() {
+    let config = parse_config(
+        r#"
+        [camera]
+        look_from = [0, 0, 5]
+        look_at = [0, 0, 0]
+        shutter = [0, 1]
+
+        [materials.red]
+        type = "lambertian"
+        albedo = [0.8, 0.1, 0.1]
+
+        [[objects]]
+        type = "sphere"
+        center = [0, 0, 0]
+        center1 = [0, 1, 0]
+        radius = 0.5
+        material = "red"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.camera.shutter(), (0.0, 1.0));
+
+    let err = parse_config(
+        r#"
+        [camera]
+        look_from = [0, 0, 5]
+        look_at = [0, 0, 0]
+        shutter = [1, 0]
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(err.to_string().contains("camera.shutter"));
+}
+
+#[test]
+fn constant_medium_work() {
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let boundary: ObjectType = Arc::new(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0, material)));
+    let ray = Ray::new(Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+
+    let dense = ConstantMedium::new(boundary.clone(), 1e9, Color::new_color(0.5, 0.5, 0.5));
+    let record = dense.hit(&ray, (1e-8, f64::INFINITY)).unwrap();
+    assert!((record.t - 4.0).abs() < 1e-6);
+    assert_eq!(dense.bounding_box().min(), Vec3(-1.0, -1.0, -1.0));
+
+    let thin = ConstantMedium::new(boundary, 1e-9, Color::new_color(0.5, 0.5, 0.5));
+    assert!(thin.hit(&ray, (1e-8, f64::INFINITY)).is_none());
+
+    // 起点位于介质内部时从起点开始采样
+    let inside = Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, -1.0), 10);
+    let record = dense.hit(&inside, (1e-8, f64::INFINITY)).unwrap();
+    assert!(record.t < 1e-6);
+
+    let (scattered, attenuation) = record
+        .hit_material
+        .scatter(inside, &record, &mut IndependentSampler::new())
+        .unwrap();
+    assert_eq!(attenuation, Color::new_color(0.5, 0.5, 0.5));
+    assert!((scattered.dir.length() - 1.0).abs() < 1e-9);
+    assert_eq!(scattered.depth, 9);
+}
+
+#[test]
+fn density_grid_work() {
+    let grid = DensityGrid::new([2, 1, 1], vec![0.0, 2.0]);
+    assert_eq!(grid.max_density(), 2.0);
+    assert_eq!(grid.density(Vec3(0.25, 0.5, 0.5)), 0.0);
+    assert_eq!(grid.density(Vec3(0.5, 0.5, 0.5)), 1.0);
+    assert_eq!(grid.density(Vec3(0.9, 0.5, 0.5)), 2.0);
+    assert_eq!(grid.density(Vec3(1.5, 0.5, 0.5)), 0.0);
+
+    let parsed = parse_grid(&encode_grid(&grid)).unwrap();
+    assert_eq!(parsed.resolution(), [2, 1, 1]);
+    assert_eq!(parsed.voxel(1, 0, 0), 2.0);
+    assert!(parse_grid(b"GRID").is_err());
+    assert!(parse_grid(&encode_grid(&grid)[..20]).is_err());
+}
+
+#[test]
+fn grid_medium_work() {
+    seed_random(1);
+    let phase: MaterialType = Arc::new(Box::new(HenyeyGreenstein::new(
+        Color::new_color(1.0, 1.0, 1.0),
+        0.5,
+    )));
+    let grid = Arc::new(DensityGrid::new([2, 2, 2], vec![1.0; 8]));
+    let bounds = AABB::new(Vec3(-1.0, -1.0, -1.0), Vec3(1.0, 1.0, 1.0));
+    let medium = GridMedium::new(grid, bounds, 0.5, phase);
+    let ray = Ray::new(Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+
+    // 均匀网格中穿出介质的比例应为透射率 exp(-σd)
+    let n = 20000;
+    let escaped = (0..n)
+        .filter(|_| medium.hit(&ray, (1e-8, f64::INFINITY)).is_none())
+        .count() as f64
+        / n as f64;
+    assert!((escaped - (-1.0f64).exp()).abs() < 0.02);
+
+    let record = (0..100)
+        .find_map(|_| medium.hit(&ray, (1e-8, f64::INFINITY)))
+        .unwrap();
+    assert!((4.0..=6.0).contains(&record.t));
+
+    // 前向散射的平均余弦应接近 g
+    let mean_cos = (0..n)
+        .map(|_| {
+            let (scattered, _) = record
+                .hit_material
+                .scatter(ray.clone(), &record, &mut IndependentSampler::new())
+                .unwrap();
+            Vec3::dot(scattered.dir, ray.dir)
+        })
+        .sum::<f64>()
+        / n as f64;
+    assert!((mean_cos - 0.5).abs() < 0.02);
+}
+
+#[test]
+fn aabb_hit_work() {
+    let aabb = AABB::new(Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0));
+    let ray = Ray::new(Vec3(-1.0, 0.5, 0.5), Vec3(1.0, 0.0, 0.0), 10);
+    assert_eq!(aabb.hit(&ray, (0.0, f64::INFINITY)), Some((1.0, 2.0)));
+
+    // 每个轴的区间都与 t_range 相交，但各轴区间互不重叠
+    let ray = Ray::new(Vec3(-1.0, 3.0, 0.5), Vec3(1.0, -1.0, 0.0), 10);
+    assert!(aabb.hit(&ray, (0.0, f64::INFINITY)).is_none());
+    assert_eq!(aabb.surface_area(), 6.0);
+    assert_eq!(aabb.centroid(), Vec3(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn bvh_sah_work() {
+    seed_random(3);
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let objects: Vec<ObjectType> = (0..200)
+        .map(|_| {
+            let center = Vec3(
+                random_range(-10.0, 10.0),
+                random_range(-10.0, 10.0),
+                random_range(-10.0, 10.0),
+            );
+            let sphere: ObjectType = Arc::new(Box::new(Sphere::new(
+                center,
+                random_range(0.1, 1.0),
+                material.clone(),
+            )));
+            sphere
+        })
+        .collect();
+
+    let sah = BVH::build_with(objects.clone(), SplitMethod::Sah);
+    let median = BVH::build_with(objects.clone(), SplitMethod::Median);
+    assert!(sah.sah_cost() < median.sah_cost());
+
+    for _ in 0..500 {
+        let ray = Ray::new(
+            Vec3(0.0, 0.0, 30.0),
+            Vec3(random_range(-0.4, 0.4), random_range(-0.4, 0.4), -1.0),
+            10,
+        );
+        let brute_force = objects
+            .iter()
+            .filter_map(|object| object.hit(&ray, (1e-8, f64::INFINITY)))
+            .map(|record| record.t)
+            .reduce(f64::min);
+        for bvh in [&sah, &median] {
+            let t = bvh.hit(&ray, (1e-8, f64::INFINITY)).map(|record| record.t);
+            assert_eq!(t, brute_force);
+        }
+    }
+
+    assert!(BVH::build(Vec::new())
+        .hit(
+            &Ray::new(Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 10),
+            (1e-8, f64::INFINITY)
+        )
+        .is_none());
+}
+
+#[test]
+fn bvh_parallel_build_work() {
+    let n = 80;
+    let mut positions = Vec::new();
+    for z in 0..=n {
+        for x in 0..=n {
+            let y = ((x * 7 + z * 13) % 5) as f64 * 0.01;
+            positions.push(Vec3(x as f64, y, z as f64));
+        }
+    }
+    let vertex = |x: usize, z: usize| VertexIndex {
+        position: z * (n + 1) + x,
+        ..Default::default()
+    };
+    let mut triangles = Vec::new();
+    for z in 0..n {
+        for x in 0..n {
+            triangles.push([vertex(x, z), vertex(x + 1, z), vertex(x + 1, z + 1)]);
+            triangles.push([vertex(x, z), vertex(x + 1, z + 1), vertex(x, z + 1)]);
+        }
+    }
+    let mesh = Arc::new(TriangleMesh::new(
+        positions,
+        Vec::new(),
+        Vec::new(),
+        triangles,
+    ));
+    let objects = mesh.objects(
+        Arc::new(Box::new(Lambertian::default())),
+        &std::collections::HashMap::new(),
+    );
+
+    let pool = |threads| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+    };
+    let serial = pool(1).install(|| BVH::build_with(objects.clone(), SplitMethod::Sah));
+    let parallel = pool(4).install(|| BVH::build_with(objects, SplitMethod::Sah));
+    let (serial_stats, parallel_stats) = (serial.stats(), parallel.stats());
+    assert_eq!(serial_stats.primitives, 2 * n * n);
+    assert_eq!(serial_stats.nodes, 2 * serial_stats.leaves - 1);
+    assert!(serial_stats.max_leaf_size <= MAX_LEAF_SIZE);
+    assert_eq!(
+        (
+            serial_stats.nodes,
+            serial_stats.leaves,
+            serial_stats.max_depth
+        ),
+        (
+            parallel_stats.nodes,
+            parallel_stats.leaves,
+            parallel_stats.max_depth
+        )
+    );
+    assert_eq!(serial.sah_cost(), parallel.sah_cost());
+
+    for i in 0..50 {
+        let ray = Ray::new(
+            Vec3(i as f64 * 1.5 + 0.3, 5.0, 40.3),
+            Vec3(0.1, -1.0, -0.2),
+            10,
+        );
+        let t = |bvh: &BVH| bvh.hit(&ray, (1e-8, f64::INFINITY)).map(|record| record.t);
+        assert!(t(&serial).is_some());
+        assert_eq!(t(&serial), t(&parallel));
+    }
+}
+
+#[test]
+fn scene_instance_work() {
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let mesh = Arc::new(
+        parse_obj("v -0.4 -0.4 0\nv 0.4 -0.4 0\nv 0.4 0.4 0\nv -0.4 0.4 0\nf 1 2 3 4\n").unwrap(),
+    );
+    let blas: ObjectType = Arc::new(Box::new(BVH::build(
+        mesh.objects(material, &std::collections::HashMap::new()),
+    )));
+
+    let mut scene = Scene::new();
+    for i in 0..1000 {
+        let matrix = Mat4::translate(Vec3((i % 40) as f64, (i / 40) as f64, 0.0));
+        assert_eq!(scene.add_instance(blas.clone(), matrix), Some(i));
+    }
+    assert!(scene
+        .add_instance(blas.clone(), Mat4::scale(Vec3(0.0, 1.0, 1.0)))
+        .is_none());
+    scene.build_bvh();
+    assert_eq!(scene.instance_count(), 1000);
+    assert_eq!(scene.bvh.stats().primitives, 1000);
+    // 所有实例共享同一个底层 BVH（每个实例与其变换各持有一份引用）
+    assert_eq!(Arc::strong_count(&blas), 2001);
+
+    let ray = |x: f64, y: f64| Ray::new(Vec3(x, y, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+    assert!(scene.hit(&ray(7.0, 3.0), (1e-8, f64::INFINITY)).is_some());
+    assert!(scene.hit(&ray(7.5, 3.0), (1e-8, f64::INFINITY)).is_none());
+
+    let id = 3 * 40 + 7;
+    let moved = Mat4::translate(Vec3(7.5, 3.0, -1.0));
+    scene.set_instance_matrix(id, moved).unwrap();
+    scene.build_bvh();
+    assert_eq!(scene.instance_matrix(id), moved);
+    assert!(scene.hit(&ray(7.0, 3.0), (1e-8, f64::INFINITY)).is_none());
+    let record = scene.hit(&ray(7.5, 3.0), (1e-8, f64::INFINITY)).unwrap();
+    assert_eq!(record.t, 6.0);
+}
+
+#[test]
+fn bvh_refit_work() {
+    seed_random(5);
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let sphere = |center: Vec3| -> ObjectType {
+        Arc::new(Box::new(Sphere::new(center, 0.3, material.clone())))
+    };
+    let mut objects: Vec<ObjectType> = (0..300)
+        .map(|i| sphere(Vec3((i % 20) as f64, (i / 20) as f64, 0.0)))
+        .collect();
+    let mut bvh = BVH::build(objects.clone());
+    let nodes = bvh.stats().nodes;
+
+    let check = |bvh: &BVH, objects: &[ObjectType]| {
+        for _ in 0..300 {
+            let ray = Ray::new(
+                Vec3(random_range(-1.0, 20.0), random_range(-1.0, 15.0), 10.0),
+                Vec3(0.0, 0.0, -1.0),
+                10,
+            );
+            let brute_force = objects
+                .iter()
+                .filter_map(|object| object.hit(&ray, (1e-8, f64::INFINITY)))
+                .map(|record| record.t)
+                .reduce(f64::min);
+            let t = bvh.hit(&ray, (1e-8, f64::INFINITY)).map(|record| record.t);
+            assert_eq!(t, brute_force);
+        }
+    };
+
+    // 小幅移动只更新包围盒，结构不变
+    for i in (0..300).step_by(7) {
+        let center = Vec3((i % 20) as f64 + 0.2, (i / 20) as f64, 0.5);
+        objects[i] = sphere(center);
+        bvh.replace(i, objects[i].clone());
+    }
+    assert!(!bvh.refit());
+    assert_eq!(bvh.stats().nodes, nodes);
+    check(&bvh, &objects);
+    assert!(!bvh.refit());
+
+    // 打乱全部位置后质量明显下降，触发完全重建
+    for (i, object) in objects.iter_mut().enumerate() {
+        *object = sphere(Vec3(((i * 37) % 20) as f64, ((i * 11) % 15) as f64, 0.0));
+        bvh.replace(i, object.clone());
+    }
+    assert!(bvh.refit());
+    check(&bvh, &objects);
+}
+
+#[test]
+fn scene_refit_work() {
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let blas: ObjectType = Arc::new(Box::new(BVH::build(vec![Arc::new(Box::new(Sphere::new(
+        Vec3(0.0, 0.0, 0.0),
+        0.4,
+        material,
+    )))])));
+
+    let mut scene = Scene::new();
+    for i in 0..100 {
+        let matrix = Mat4::translate(Vec3(i as f64, 0.0, 0.0));
+        scene.add_instance(blas.clone(), matrix).unwrap();
+    }
+    scene.build_bvh();
+
+    let ray = |x: f64, y: f64| Ray::new(Vec3(x, y, 5.0), Vec3(0.0, 0.0, -1.0), 10);
+    for frame in 1..=3 {
+        for i in 0..100 {
+            let matrix = Mat4::translate(Vec3(i as f64, 0.1 * frame as f64, 0.0));
+            scene.set_instance_matrix(i, matrix).unwrap();
+        }
+        assert!(!scene.refit_bvh());
+        // 实例未移动时这条光线会从球的上方掠过
+        let y = 0.1 * frame as f64 + 0.35;
+        assert!(scene.hit(&ray(42.0, y), (1e-8, f64::INFINITY)).is_some());
+    }
+
+    // 新增实例后无法重新拟合，改为重建
+    scene.add_instance(blas, Mat4::translate(Vec3(0.5, 5.0, 0.0)));
+    assert!(scene.refit_bvh());
+    assert!(scene.hit(&ray(0.5, 5.0), (1e-8, f64::INFINITY)).is_some());
+}
+
+#[test]
+fn tile_order_work() {
+    for order in [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert] {
+        let tiles = generate_tiles(100, 70, 16, order);
+        assert_eq!(tiles.len(), 7 * 5);
+        let mut covered = vec![0; 100 * 70];
+        for tile in &tiles {
+            for (x, y) in tile.pixels() {
+                covered[(y * 100 + x) as usize] += 1;
+            }
+        }
+        assert!(covered.iter().all(|count| *count == 1));
+        assert_eq!(order.to_string().parse::<TileOrder>(), Ok(order));
+    }
+    assert!("zigzag".parse::<TileOrder>().is_err());
+
+    // 螺旋从中心区块开始
+    let first = generate_tiles(100, 70, 16, TileOrder::Spiral)[0];
+    assert_eq!((first.x, first.y), (48, 32));
+
+    // Hilbert 曲线上相邻的区块在图像中也相邻
+    let tiles = generate_tiles(128, 128, 16, TileOrder::Hilbert);
+    for pair in tiles.windows(2) {
+        let distance = pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y);
+        assert_eq!(distance, 16);
+    }
+}
+
+#[test]
+fn render_progress_work() {
+    let mut config = Config::builtin("three_spheres").unwrap();
+    config.image_width = 30;
+    config.image_height = 20;
+    config.samples_per_pixel = 2;
+    config.tile_size = 8;
+    let tile_count = generate_tiles(30, 20, 8, config.tile_order).len();
+
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let mut renderer = Renderer::new(Arc::new(Box::new(config)));
+    let sink = reports.clone();
+    renderer.set_progress(move |progress| sink.borrow_mut().push(*progress));
+    renderer.render().unwrap();
+
+    let reports = reports.borrow();
+    assert_eq!(reports.len(), tile_count);
+    for pair in reports.windows(2) {
+        assert!(pair[0].completed_samples < pair[1].completed_samples);
+        assert!(pair[0].rays <= pair[1].rays);
+    }
+    let last = reports.last().unwrap();
+    assert!(last.is_finished());
+    assert_eq!(last.total_tiles, tile_count);
+    assert_eq!(last.completed_samples, 30 * 20 * 2);
+    assert_eq!(last.completed_samples, last.total_samples);
+    assert!(last.rays >= last.total_samples);
+    assert_eq!(last.fraction(), 1.0);
+    assert_eq!(last.eta(), Some(Duration::ZERO));
+}
+
+#[test]
+fn progressive_render_work() {
+    assert_eq!(sample_passes(1), vec![1]);
+    assert_eq!(sample_passes(8), vec![1, 1, 2, 4]);
+    assert_eq!(sample_passes(10), vec![1, 1, 2, 4, 2]);
+    assert!(sample_passes(0).is_empty());
+
+    let path = std::env::temp_dir().join("rtweekend_progressive_snapshot.png");
+    let _ = std::fs::remove_file(&path);
+    let mut config = Config::builtin("three_spheres").unwrap();
+    config.file_path = path.display().to_string();
+    config.image_width = 24;
+    config.image_height = 16;
+    config.samples_per_pixel = 5;
+    config.progressive = true;
+    config.snapshot_interval = Duration::ZERO;
+
+    let passes = Rc::new(RefCell::new(Vec::new()));
+    let mut renderer = Renderer::new(Arc::new(Box::new(config)));
+    let sink = passes.clone();
+    renderer.set_progress(move |progress| {
+        sink.borrow_mut()
+            .push((progress.completed_passes, progress.total_passes))
+    });
+    renderer.render().unwrap();
+
+    // 累计 1、2、4、5 个采样，共四遍
+    assert_eq!(renderer.samples(), 5);
+    assert_eq!(passes.borrow().last(), Some(&(4, 4)));
+    // 最后一遍之前已写出过中间结果
+    let snapshot = image::open(&path).unwrap();
+    assert_eq!((snapshot.width(), snapshot.height()), (24, 16));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn hdr_output_work() {
+    assert_eq!(
+        OutputFormat::from_path("a.EXR"),
+        Some(OutputFormat::OpenExr)
+    );
+    assert_eq!(OutputFormat::from_path("a.hdr"), Some(OutputFormat::Hdr));
+    assert_eq!(OutputFormat::from_path("a.pfm"), Some(OutputFormat::Pfm));
+    assert_eq!(
+        OutputFormat::from_path("a.png"),
+        Some(OutputFormat::Ldr(image::ImageFormat::Png))
+    );
+    assert!(!OutputFormat::from_path("a.png").unwrap().is_hdr());
+    assert_eq!(OutputFormat::from_path("a.xyz"), None);
+    assert_eq!(OutputFormat::from_path("image"), None);
+
+    // 超过 1 的辐亮度在高动态范围格式中得以保留
+    let image = image::Rgb32FImage::from_fn(4, 3, |x, y| {
+        image::Rgb([x as f32 * 2.0, y as f32 * 0.25, 8.0])
+    });
+    for extension in ["exr", "hdr"] {
+        let path = std::env::temp_dir().join(format!("rtweekend_output.{}", extension));
+        save_image(&image, &path, &ToneMapping::default()).unwrap();
+        let loaded = open_linear_image(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.dimensions(), (4, 3));
+        for (expected, actual) in image.pixels().zip(loaded.pixels()) {
+            for (e, a) in expected.0.iter().zip(actual.0) {
+                assert!((e - a).abs() <= e * 0.01, "{}: {} vs {}", extension, e, a);
+            }
+        }
+    }
+
+    // PFM 的扫描行自下而上
+    let bytes = encode_pfm(&image);
+    let header = b"PF\n4 3\n-1.0\n";
+    assert_eq!(&bytes[..header.len()], header);
+    assert_eq!(bytes.len(), header.len() + 4 * 3 * 3 * 4);
+    let first =
+        |offset: usize| f32::from_le_bytes(bytes[header.len() + offset..][..4].try_into().unwrap());
+    assert_eq!((first(0), first(4), first(8)), (0.0, 0.5, 8.0));
+
+    // 低动态范围格式截断并做 gamma 校正
+    let ldr = quantize(&image, &ToneMapping::default());
+    assert_eq!(ldr.get_pixel(3, 0).0[0], 255);
+    assert_eq!(ldr.get_pixel(0, 0).0[0], 0);
+}
+
+#[test]
+fn tone_mapping_work() {
+    let gray = |c: f64| Color::new_color(c, c, c);
+    let mapped = |operator: ToneMapper, c: f64| {
+        let tone_mapping = ToneMapping {
+            operator,
+            ..ToneMapping::default()
+        };
+        tone_mapping.apply(gray(c)).0
+    };
+
+    assert_eq!(mapped(ToneMapper::Clamp, 0.5), 0.5);
+    assert_eq!(mapped(ToneMapper::Clamp, 3.0), 1.0);
+    assert_eq!(mapped(ToneMapper::Reinhard, 1.0), 0.5);
+    assert!((mapped(ToneMapper::ExtendedReinhard, DEFAULT_WHITE_POINT) - 1.0).abs() < 1e-12);
+    for operator in [
+        ToneMapper::Clamp,
+        ToneMapper::Reinhard,
+        ToneMapper::ExtendedReinhard,
+        ToneMapper::Aces,
+        ToneMapper::Hable,
+    ] {
+        // 单调且落在 [0, 1]，亮部不再被硬截断（clamp 除外）
+        let values: Vec<f64> = [0.0, 0.1, 0.5, 1.0, 4.0, 100.0]
+            .iter()
+            .map(|c| mapped(operator, *c))
+            .collect();
+        assert!(values[0].abs() < 1e-3, "{}", operator);
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(values.iter().all(|v| (0.0..=1.0).contains(v)));
+        if operator != ToneMapper::Clamp {
+            assert!(values[3] < values[4], "{}", operator);
+        }
+        assert_eq!(operator.to_string().parse::<ToneMapper>(), Ok(operator));
+    }
+    assert!("filmic".parse::<ToneMapper>().is_err());
+
+    // 曝光每增加 1 EV 亮度加倍
+    let brighter = ToneMapping {
+        exposure: 1.0,
+        ..ToneMapping::default()
+    };
+    assert_eq!(brighter.apply(gray(0.25)), gray(0.5));
+
+    // sRGB 曲线
+    assert_eq!(linear_to_srgb(0.0), 0.0);
+    assert!((linear_to_srgb(1.0) - 1.0).abs() < 1e-12);
+    assert!((linear_to_srgb(0.0031308) - 0.04045).abs() < 1e-6);
+    assert_eq!(ToneMapping::default().to_rgb8(gray(0.5)).0, [188, 188, 188]);
+    assert_eq!(ToneMapping::default().to_rgb8(gray(-1.0)).0, [0, 0, 0]);
+
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [film]
+        tone_map = "aces"
+        exposure = -1.5
+
+        [materials.gray]
+        type = "lambertian"
+        albedo = [0.5, 0.5, 0.5]
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, 0.0]
+        radius = 1.0
+        material = "gray"
+    "#;
+    let config = parse_config(text).unwrap();
+    assert_eq!(config.tone_mapping.operator, ToneMapper::Aces);
+    assert_eq!(config.tone_mapping.exposure, -1.5);
+    let message = parse_config(&text.replace("aces", "filmic"))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(message.contains("film.tone_map"), "{}", message);
+}
+
+#[test]
+fn reconstruction_filter_work() {
+    assert_eq!(Filter::default(), Filter::new(FilterKind::Box));
+    assert_eq!(Filter::default().radius, 0.5);
+    for kind in [
+        FilterKind::Box,
+        FilterKind::Tent,
+        FilterKind::Gaussian,
+        FilterKind::Mitchell,
+        FilterKind::Lanczos,
+    ] {
+        let filter = Filter::new(kind);
+        let r = filter.radius;
+        assert!(filter.evaluate(0.0, 0.0) > 0.0, "{}", kind);
+        assert_eq!(filter.evaluate(r, 0.0), 0.0, "{}", kind);
+        assert_eq!(filter.evaluate(0.3, -0.2), filter.evaluate(-0.3, 0.2));
+        assert_eq!(
+            filter.evaluate(0.3, 0.2),
+            filter.evaluate_1d(0.3) * filter.evaluate_1d(0.2)
+        );
+        assert_eq!(kind.to_string().parse::<FilterKind>(), Ok(kind));
+    }
+    assert!("sinc".parse::<FilterKind>().is_err());
+    // Mitchell 与 Lanczos 有负瓣
+    assert!(Filter::new(FilterKind::Mitchell).evaluate_1d(1.5) < 0.0);
+    assert!(Filter::new(FilterKind::Lanczos).evaluate_1d(1.5) < 0.0);
+
+    // 胶片以 f32 保存
+    let close = |lhs: Color, rhs: Color| (lhs - rhs).length() < 1e-6;
+
+    // 半径 0.5 的盒式滤波器只落在采样所在的像素
+    let color = Color::new_color(0.2, 0.4, 0.6);
+    let mut film = Film::new(4, 4, Filter::default());
+    film.add_sample((1.3, 2.7), color);
+    assert!(close(film.pixel(1, 2), color));
+    assert_eq!(film.pixel(1, 1), Color::default());
+
+    // 落在两个像素中心正中的采样以相同权重分给两边，像素值为加权平均
+    let mut film = Film::new(4, 4, Filter::new(FilterKind::Tent));
+    film.add_sample((2.0, 2.5), color);
+    film.add_sample((2.5, 2.5), color * 3.0);
+    assert!(close(film.pixel(1, 2), color));
+    assert!(close(film.pixel(2, 2), color * (0.5 + 3.0) / 1.5));
+    assert_eq!(film.pixel(2, 1), Color::default());
+
+    // 直接构造的滤波器半径可以超过 MAX_FILTER_RADIUS，半径内的每一列仍然得到权重
+    let mut film = Film::new(40, 40, Filter::new(FilterKind::Tent).with_radius(12.0));
+    film.add_sample((20.0, 20.0), color);
+    for x in 8..32 {
+        assert!(close(film.pixel(x, 19), color), "{}", x);
+    }
+    assert_eq!(film.pixel(7, 19), Color::default());
+    assert_eq!(film.pixel(32, 19), Color::default());
+
+    // 按区块分别溅射后合并与直接溅射到整幅胶片一致
+    let filter = Filter::new(FilterKind::Mitchell);
+    let mut direct = Film::new(20, 12, filter);
+    let mut merged = Film::new(20, 12, filter);
+    seed_random(11);
+    for tile in generate_tiles(20, 12, 8, TileOrder::Hilbert) {
+        let mut local = Film::for_tile(tile, 20, 12, filter);
+        for (x, y) in tile.pixels() {
+            let position = (x as f64 + random_01(), y as f64 + random_01());
+            let color = Color::new_color(random_01(), random_01(), random_01());
+            direct.add_sample(position, color);
+            local.add_sample(position, color);
+        }
+        merged.merge(&local);
+    }
+    for y in 0..12 {
+        for x in 0..20 {
+            assert!((direct.pixel(x, y) - merged.pixel(x, y)).length() < 1e-5);
+        }
+    }
+
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [film]
+        filter = "gaussian"
+        filter_radius = 2.0
+
+        [materials.gray]
+        type = "lambertian"
+        albedo = [0.5, 0.5, 0.5]
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, 0.0]
+        radius = 1.0
+        material = "gray"
+    "#;
+    let config = parse_config(text).unwrap();
+    assert_eq!(
+        config.filter,
+        Filter::new(FilterKind::Gaussian).with_radius(2.0)
+    );
+    let message = parse_config(&text.replace("2.0\n", "-1.0\n"))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(message.contains("film.filter_radius"), "{}", message);
+}
+
+#[test]
+fn sampler_work() {
+    let kinds = [
+        SamplerKind::Independent,
+        SamplerKind::Stratified,
+        SamplerKind::Halton,
+        SamplerKind::Sobol,
+        SamplerKind::BlueNoise,
+    ];
+    for kind in kinds {
+        assert_eq!(kind.to_string().parse::<SamplerKind>(), Ok(kind));
+
+        // 同一像素的同一采样重复取得相同的值（独立采样器需重置随机数种子）
+        let mut sampler = kind.create(16, 7);
+        let draw = |sampler: &mut Box<dyn Sampler>| {
+            seed_random(3);
+            sampler.start_pixel_sample((5, 9), 11);
+            let (a, b) = sampler.get_2d();
+            vec![a, b, sampler.get_1d(), sampler.get_2d().1]
+        };
+        let values = draw(&mut sampler);
+        assert_eq!(values, draw(&mut sampler), "{}", kind);
+        assert!(values.iter().all(|v| (0.0..1.0).contains(v)), "{}", kind);
+    }
+    assert!("random".parse::<SamplerKind>().is_err());
+
+    // 16 个采样在第一个二维维度上各占 4x4 网格的一格，在第一个一维维度上各占 1/16 区间
+    for kind in [SamplerKind::Stratified, SamplerKind::Sobol] {
+        let mut sampler = kind.create(16, 1);
+        let mut cells = [0; 16];
+        let mut intervals = [0; 16];
+        for index in 0..16 {
+            sampler.start_pixel_sample((2, 3), index);
+            let (u, v) = sampler.get_2d();
+            cells[(v * 4.0) as usize * 4 + (u * 4.0) as usize] += 1;
+            intervals[(sampler.get_1d() * 16.0) as usize] += 1;
+        }
+        assert!(cells.iter().all(|count| *count == 1), "{}", kind);
+        assert!(intervals.iter().all(|count| *count == 1), "{}", kind);
+    }
+    let mut halton = HaltonSampler::new(1);
+    let mut intervals = [0; 16];
+    for index in 0..16 {
+        halton.start_pixel_sample((2, 3), index);
+        intervals[(halton.get_1d() * 16.0) as usize] += 1;
+    }
+    assert!(intervals.iter().all(|count| *count == 1));
+
+    // 以四分之一圆盘的面积为被积函数，低差异采样器的误差明显小于独立采样
+    let error = |kind: SamplerKind| {
+        seed_random(5);
+        let mut sampler = kind.create(16, 9);
+        let mut squared_error = 0.0;
+        for pixel in 0..256 {
+            let mut inside = 0;
+            for index in 0..16 {
+                sampler.start_pixel_sample((pixel % 16, pixel / 16), index);
+                sampler.get_2d();
+                let (u, v) = sampler.get_2d();
+                inside += (u * u + v * v < 1.0) as u32;
+            }
+            squared_error += (inside as f64 / 16.0 - std::f64::consts::FRAC_PI_4).powi(2);
+        }
+        squared_error / 256.0
+    };
+    let independent = error(SamplerKind::Independent);
+    for kind in &kinds[1..] {
+        assert!(error(*kind) < 0.7 * independent, "{}", kind);
+    }
+
+    // 蓝噪声掩码是 [0, 1) 的均匀分层，相邻像素的值相差较大（白噪声的期望为 1/3）
+    let mut values: Vec<f64> = (0..BLUE_NOISE_SIZE * BLUE_NOISE_SIZE)
+        .map(|i| blue_noise(i % BLUE_NOISE_SIZE, i / BLUE_NOISE_SIZE))
+        .collect();
+    let neighbor_difference = (0..BLUE_NOISE_SIZE * BLUE_NOISE_SIZE)
+        .map(|i| {
+            let (x, y) = (i % BLUE_NOISE_SIZE, i / BLUE_NOISE_SIZE);
+            (blue_noise(x, y) - blue_noise(x + 1, y)).abs()
+        })
+        .sum::<f64>()
+        / values.len() as f64;
+    assert!(neighbor_difference > 0.38, "{}", neighbor_difference);
+    values.sort_by(f64::total_cmp);
+    for (rank, value) in values.iter().enumerate() {
+        assert!((value * values.len() as f64 - rank as f64 - 0.5).abs() < 1e-9);
+    }
+
+    // 指定种子时渲染结果可复现
+    let mut config = Config::builtin("three_spheres").unwrap();
+    config.seed = 4;
+    config.samples_per_pixel = 4;
+    assert_eq!(
+        render_pixel(&config, 600, 400),
+        render_pixel(&config, 600, 400)
+    );
+}
+
+#[test]
+fn deterministic_render_work() {
+    let render = |threads: usize, tile_size: u32, seed: u64| {
+        let mut config = Config::builtin("three_spheres").unwrap();
+        config.image_width = 24;
+        config.image_height = 16;
+        config.samples_per_pixel = 3;
+        config.threads = threads;
+        config.tile_size = tile_size;
+        config.tile_order = TileOrder::Spiral;
+        config.seed = seed;
+        config.filter = Filter::new(FilterKind::Gaussian);
+        config.sampler = SamplerKind::Independent;
+        let mut renderer = Renderer::new(Arc::new(Box::new(config)));
+        renderer.render().unwrap();
+        renderer.linear_image()
+    };
+
+    // 线程数与区块完成顺序不影响结果，区块划分相同时逐位相同
+    let reference = render(1, 4, 9);
+    assert_eq!(reference, render(3, 4, 9));
+    assert_eq!(reference, render(4, 4, 9));
+    assert_ne!(reference, render(3, 4, 10));
+
+    // 同一像素的同一采样总得到相同的随机序列，与之前取过多少随机数无关
+    let mut config = Config::builtin("three_spheres").unwrap();
+    config.seed = 2;
+    config.samples_per_pixel = 2;
+    config.sampler = SamplerKind::Stratified;
+    let expected = render_pixel(&config, 300, 200);
+    seed_random(123);
+    random_01();
+    assert_eq!(render_pixel(&config, 300, 200), expected);
+
+    let mut samples = Vec::new();
+    let mut sampler = config.create_sampler();
+    render_samples(&config, sampler.as_mut(), 300, 200, 0, 2, |_, color| {
+        samples.push(color)
+    });
+    let mut later = Vec::new();
+    render_samples(&config, sampler.as_mut(), 300, 200, 1, 1, |_, color| {
+        later.push(color)
+    });
+    assert_eq!(samples[1], later[0]);
+
+    seed_random_stream(5, 1);
+    let first = random_01();
+    seed_random_stream(5, 2);
+    assert_ne!(random_01(), first);
+    seed_random_stream(5, 1);
+    assert_eq!(random_01(), first);
+
+    // 中位数划分沿最长轴，构建结果不再随机
+    seed_random(8);
+    let material: MaterialType =
+        Arc::new(Box::new(Lambertian::new(Color::new_color(0.5, 0.5, 0.5))));
+    let objects: Vec<ObjectType> = (0..200)
+        .map(|_| {
+            let center = Point3::new_point3(
+                random_range(-50.0, 50.0),
+                random_range(-1.0, 1.0),
+                random_range(-5.0, 5.0),
+            );
+            let sphere: ObjectType = Arc::new(Box::new(Sphere::new(center, 0.3, material.clone())));
+            sphere
+        })
+        .collect();
+    let first = BVH::build_with(objects.clone(), SplitMethod::Median);
+    let second = BVH::build_with(objects, SplitMethod::Median);
+    assert_eq!(first.sah_cost(), second.sah_cost());
+    assert_eq!(first.stats().nodes, second.stats().nodes);
+    assert_eq!(first.stats().max_depth, second.stats().max_depth);
+}
+
+#[test]
+fn scene_file_film_size_work() {
+    let text = r#"
+        [camera]
+        look_from = [0.0, 0.0, 1.0]
+        look_at = [0.0, 0.0, 0.0]
+
+        [film]
+        image_width = 4
+        image_height = 3
+
+        [materials.gray]
+        type = "lambertian"
+        albedo = [0.5, 0.5, 0.5]
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 0.0, 0.0]
+        radius = 1.0
+        material = "gray"
+    "#;
+    assert!(parse_config(text).is_ok());
+
+    // 每个方向至少两个像素，由宽高比推出的高度同样检查
+    let message = |text: String| parse_config(&text).err().unwrap().to_string();
+    assert_eq!(
+        message(text.replace("image_width = 4", "image_width = 1")),
+        "`film.image_width`: must be at least 2"
+    );
+    assert_eq!(
+        message(text.replace("image_height = 3", "image_height = 1")),
+        "`film.image_height`: must be at least 2"
+    );
+    assert_eq!(
+        message(text.replace("image_height = 3", "aspect_ratio = 3.0")),
+        "`film.image_height`: 1 pixels derived from image_width and aspect_ratio, must be at least 2"
+    );
+}
+
+#[test]
+fn bvh_deep_traversal_work() {
+    seed_random(5);
+    let material: MaterialType = Arc::new(Box::new(Lambertian::default()));
+    let objects: Vec<ObjectType> = (0..4000)
+        .map(|_| {
+            let center = Vec3(
+                random_range(-20.0, 20.0),
+                random_range(-20.0, 20.0),
+                random_range(-20.0, 20.0),
+            );
+            let sphere: ObjectType = Arc::new(Box::new(Sphere::new(
+                center,
+                random_range(0.05, 0.5),
+                material.clone(),
+            )));
+            sphere
+        })
+        .collect();
+
+    let sah = BVH::build_with(objects.clone(), SplitMethod::Sah);
+    let median = BVH::build_with(objects.clone(), SplitMethod::Median);
+    assert!(sah.stats().max_depth >= 10);
+    assert!(median.stats().max_depth >= 10);
+
+    // 光线从场景内外任意方向出发，包含与坐标轴平行的方向与有限的 t 上限
+    let axes = [
+        Vec3(1.0, 0.0, 0.0),
+        Vec3(0.0, -1.0, 0.0),
+        Vec3(0.0, 0.0, 1.0),
+    ];
+    for i in 0..3000 {
+        let origin = Vec3(
+            random_range(-30.0, 30.0),
+            random_range(-30.0, 30.0),
+            random_range(-30.0, 30.0),
+        );
+        let dir = match i % 10 {
+            0 => axes[i % 3],
+            _ => random_unit_sphere(),
+        };
+        let ray = Ray::new(origin, dir, 10);
+        let t_range = match i % 4 {
+            0 => (1e-8, random_range(1.0, 20.0)),
+            _ => (1e-8, f64::INFINITY),
+        };
+
+        let brute_force = objects
+            .iter()
+            .filter_map(|object| object.hit(&ray, t_range))
+            .map(|record| (record.t, record.hit_point))
+            .reduce(|lhs, rhs| if rhs.0 < lhs.0 { rhs } else { lhs });
+        for bvh in [&sah, &median] {
+            let result = bvh
+                .hit(&ray, t_range)
+                .map(|record| (record.t, record.hit_point));
+            assert_eq!(result, brute_force);
+        }
+    }
+}